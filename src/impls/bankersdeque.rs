@@ -0,0 +1,150 @@
+/// 8.4.2 銀行家の両端キュー
+use std::fmt;
+
+use super::{Stream, Deque};
+
+// c は 2 か 3 が推奨されている
+pub const DEFAULT_BALANCE: usize = 3;
+
+// |F| <= c|R| + 1 かつ |R| <= c|F| + 1 を保つ
+pub struct BankersDeque<T> {
+    c: usize,
+    lenf: usize,
+    front: Stream<T>,
+    lenr: usize,
+    rear: Stream<T>
+}
+
+impl <T> Clone for BankersDeque<T> {
+    fn clone(&self) -> BankersDeque<T> {
+        BankersDeque {
+            c: self.c,
+            lenf: self.lenf,
+            front: self.front.clone(),
+            lenr: self.lenr,
+            rear: self.rear.clone()
+        }
+    }
+}
+
+impl <T> BankersDeque<T>
+    where T: Clone + 'static
+{
+    pub fn with_balance(c: usize) -> BankersDeque<T> {
+        assert!(c >= 2, "balance constant must be at least 2");
+        BankersDeque {c, lenf: 0, front: Stream::empty(), lenr: 0, rear: Stream::empty()}
+    }
+
+    pub fn balance(&self) -> usize {
+        self.c
+    }
+
+    pub fn len(&self) -> usize {
+        self.lenf + self.lenr
+    }
+
+    fn check(c: usize, lenf: usize, front: Stream<T>, lenr: usize, rear: Stream<T>) -> BankersDeque<T> {
+        if lenf > c * lenr + 1 {
+            let i = (lenf + lenr) / 2;
+            let j = lenf + lenr - i;
+            let rear = rear.append(&front.drop(i).reverse());
+            let front = front.take(i);
+            BankersDeque {c, lenf: i, front, lenr: j, rear}
+        } else if lenr > c * lenf + 1 {
+            let j = (lenf + lenr) / 2;
+            let i = lenf + lenr - j;
+            let front = front.append(&rear.drop(j).reverse());
+            let rear = rear.take(j);
+            BankersDeque {c, lenf: i, front, lenr: j, rear}
+        } else {
+            BankersDeque {c, lenf, front, lenr, rear}
+        }
+    }
+}
+
+impl <T> Deque<T> for BankersDeque<T>
+    where T: Clone + 'static
+{
+    fn empty() -> BankersDeque<T> {
+        BankersDeque::with_balance(DEFAULT_BALANCE)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lenf + self.lenr == 0
+    }
+
+    fn cons(&self, x: T) -> BankersDeque<T> {
+        BankersDeque::check(self.c,
+                            self.lenf + 1, Stream::cons(x, self.front.clone()),
+                            self.lenr, self.rear.clone())
+    }
+
+    // 不変条件より、front が空なら rear の要素は高々 1 つ
+    fn head(&self) -> &T {
+        if self.lenf == 0 {
+            if self.lenr == 0 {
+                panic!("empty deque")
+            }
+            self.rear.head()
+        } else {
+            self.front.head()
+        }
+    }
+
+    fn tail(&self) -> BankersDeque<T> {
+        if self.lenf == 0 {
+            if self.lenr == 0 {
+                panic!("empty deque")
+            }
+            BankersDeque::with_balance(self.c)
+        } else {
+            BankersDeque::check(self.c,
+                                self.lenf - 1, self.front.tail().clone(),
+                                self.lenr, self.rear.clone())
+        }
+    }
+
+    fn snoc(&self, x: T) -> BankersDeque<T> {
+        BankersDeque::check(self.c,
+                            self.lenf, self.front.clone(),
+                            self.lenr + 1, Stream::cons(x, self.rear.clone()))
+    }
+
+    fn last(&self) -> &T {
+        if self.lenr == 0 {
+            if self.lenf == 0 {
+                panic!("empty deque")
+            }
+            self.front.head()
+        } else {
+            self.rear.head()
+        }
+    }
+
+    fn init(&self) -> BankersDeque<T> {
+        if self.lenr == 0 {
+            if self.lenf == 0 {
+                panic!("empty deque")
+            }
+            BankersDeque::with_balance(self.c)
+        } else {
+            BankersDeque::check(self.c,
+                                self.lenf, self.front.clone(),
+                                self.lenr - 1, self.rear.tail().clone())
+        }
+    }
+}
+
+impl <T> fmt::Debug for BankersDeque<T>
+    where T: fmt::Debug + Clone + 'static
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rear = self.rear.reverse();
+        write!(f, "[")?;
+        for (i, v) in self.front.iter().chain(rear.iter()).enumerate() {
+            if i != 0 { write!(f, ", ")? }
+            write!(f, "{:?}", v)?;
+        }
+        write!(f, "]")
+    }
+}
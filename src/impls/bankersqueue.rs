@@ -0,0 +1,84 @@
+/// 6.3.2 銀行家のキュー
+use std::fmt;
+
+use super::{Stream, Queue};
+
+// |R| <= |F| を保つ。反転は遅延されるので、古いバージョンで何度 tail しても
+// 反転は一度しか行われない
+pub struct BankersQueue<T> {
+    lenf: usize,
+    front: Stream<T>,
+    lenr: usize,
+    rear: Stream<T>
+}
+
+impl <T> Clone for BankersQueue<T> {
+    fn clone(&self) -> BankersQueue<T> {
+        BankersQueue {
+            lenf: self.lenf,
+            front: self.front.clone(),
+            lenr: self.lenr,
+            rear: self.rear.clone()
+        }
+    }
+}
+
+impl <T> BankersQueue<T>
+    where T: Clone + 'static
+{
+    pub fn len(&self) -> usize {
+        self.lenf + self.lenr
+    }
+
+    fn check(lenf: usize, front: Stream<T>, lenr: usize, rear: Stream<T>) -> BankersQueue<T> {
+        if lenr <= lenf {
+            BankersQueue {lenf, front, lenr, rear}
+        } else {
+            BankersQueue {lenf: lenf + lenr, front: front.append(&rear.reverse()), lenr: 0, rear: Stream::empty()}
+        }
+    }
+}
+
+impl <T> Queue<T> for BankersQueue<T>
+    where T: Clone + 'static
+{
+    fn empty() -> BankersQueue<T> {
+        BankersQueue {lenf: 0, front: Stream::empty(), lenr: 0, rear: Stream::empty()}
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lenf == 0
+    }
+
+    fn snoc(&self, x: T) -> BankersQueue<T> {
+        BankersQueue::check(self.lenf, self.front.clone(), self.lenr + 1, Stream::cons(x, self.rear.clone()))
+    }
+
+    fn head(&self) -> &T {
+        if self.lenf == 0 {
+            panic!("empty queue")
+        }
+        self.front.head()
+    }
+
+    fn tail(&self) -> BankersQueue<T> {
+        if self.lenf == 0 {
+            panic!("empty queue")
+        }
+        BankersQueue::check(self.lenf - 1, self.front.tail().clone(), self.lenr, self.rear.clone())
+    }
+}
+
+impl <T> fmt::Debug for BankersQueue<T>
+    where T: fmt::Debug + Clone + 'static
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rear = self.rear.reverse();
+        write!(f, "[")?;
+        for (i, v) in self.front.iter().chain(rear.iter()).enumerate() {
+            if i != 0 { write!(f, ", ")? }
+            write!(f, "{:?}", v)?;
+        }
+        write!(f, "]")
+    }
+}
@@ -0,0 +1,112 @@
+/// 演習 5.1 両端キュー
+use std::fmt;
+
+use super::{Stack, List, Deque};
+
+// front と rear のどちらかが空になったら、もう片方を半分に分けて
+// 前半と後半を振り分け直す。要素数が 2 以上ならば両方とも空でない。
+#[derive(Clone)]
+pub struct BatchedDeque<T> {
+    lenf: usize,
+    front: List<T>,
+    lenr: usize,
+    rear: List<T>
+}
+
+impl <T> BatchedDeque<T>
+    where T: Clone
+{
+    pub fn len(&self) -> usize {
+        self.lenf + self.lenr
+    }
+
+    fn check(lenf: usize, front: List<T>, lenr: usize, rear: List<T>) -> BatchedDeque<T> {
+        if lenf == 0 && lenr > 1 {
+            let (rear, front) = rear.split_at(lenr / 2);
+            BatchedDeque {lenf: lenr - lenr / 2, front: front.reverse(), lenr: lenr / 2, rear}
+        } else if lenr == 0 && lenf > 1 {
+            let (front, rear) = front.split_at(lenf / 2);
+            BatchedDeque {lenf: lenf / 2, front, lenr: lenf - lenf / 2, rear: rear.reverse()}
+        } else {
+            BatchedDeque {lenf, front, lenr, rear}
+        }
+    }
+}
+
+impl <T> Deque<T> for BatchedDeque<T>
+    where T: Clone
+{
+    fn empty() -> BatchedDeque<T> {
+        BatchedDeque {lenf: 0, front: List::Nil, lenr: 0, rear: List::Nil}
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lenf + self.lenr == 0
+    }
+
+    fn cons(&self, x: T) -> BatchedDeque<T> {
+        BatchedDeque::check(self.lenf + 1, self.front.snoc(x), self.lenr, self.rear.clone())
+    }
+
+    fn head(&self) -> &T {
+        if self.lenf == 0 {
+            if self.lenr == 0 {
+                panic!("empty deque")
+            }
+            self.rear.head()
+        } else {
+            self.front.head()
+        }
+    }
+
+    fn tail(&self) -> BatchedDeque<T> {
+        if self.lenf == 0 {
+            if self.lenr == 0 {
+                panic!("empty deque")
+            }
+            Deque::empty()
+        } else {
+            BatchedDeque::check(self.lenf - 1, self.front.tail().clone(), self.lenr, self.rear.clone())
+        }
+    }
+
+    fn snoc(&self, x: T) -> BatchedDeque<T> {
+        BatchedDeque::check(self.lenf, self.front.clone(), self.lenr + 1, self.rear.snoc(x))
+    }
+
+    fn last(&self) -> &T {
+        if self.lenr == 0 {
+            if self.lenf == 0 {
+                panic!("empty deque")
+            }
+            self.front.head()
+        } else {
+            self.rear.head()
+        }
+    }
+
+    fn init(&self) -> BatchedDeque<T> {
+        if self.lenr == 0 {
+            if self.lenf == 0 {
+                panic!("empty deque")
+            }
+            Deque::empty()
+        } else {
+            BatchedDeque::check(self.lenf, self.front.clone(), self.lenr - 1, self.rear.tail().clone())
+        }
+    }
+}
+
+impl <T> fmt::Debug for BatchedDeque<T>
+    where T: fmt::Debug + Clone
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rear = self.rear.reverse();
+        write!(f, "[")?;
+        for (i, v) in self.front.iter().chain(rear.iter()).enumerate() {
+            if i != 0 { write!(f, ", ")? }
+            write!(f, "{:?}", v)?;
+        }
+        write!(f, "]")
+    }
+}
@@ -0,0 +1,68 @@
+/// 5.2 キュー
+use std::fmt;
+
+use super::{Stack, List, Queue};
+
+// front が空になったら rear を反転して front にする。
+// front が空ならば rear も空。
+#[derive(Clone)]
+pub struct BatchedQueue<T> {
+    front: List<T>,
+    rear: List<T>
+}
+
+impl <T> BatchedQueue<T>
+    where T: Clone
+{
+    fn check(front: List<T>, rear: List<T>) -> BatchedQueue<T> {
+        if Stack::is_empty(&front) {
+            BatchedQueue {front: rear.reverse(), rear: List::Nil}
+        } else {
+            BatchedQueue {front, rear}
+        }
+    }
+}
+
+impl <T> Queue<T> for BatchedQueue<T>
+    where T: Clone
+{
+    fn empty() -> BatchedQueue<T> {
+        BatchedQueue {front: List::Nil, rear: List::Nil}
+    }
+
+    fn is_empty(&self) -> bool {
+        Stack::is_empty(&self.front)
+    }
+
+    fn snoc(&self, x: T) -> BatchedQueue<T> {
+        BatchedQueue::check(self.front.clone(), self.rear.snoc(x))
+    }
+
+    fn head(&self) -> &T {
+        if Stack::is_empty(&self.front) {
+            panic!("empty queue")
+        }
+        self.front.head()
+    }
+
+    fn tail(&self) -> BatchedQueue<T> {
+        if Stack::is_empty(&self.front) {
+            panic!("empty queue")
+        }
+        BatchedQueue::check(self.front.tail().clone(), self.rear.clone())
+    }
+}
+
+impl <T> fmt::Debug for BatchedQueue<T>
+    where T: fmt::Debug + Clone
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rear = self.rear.reverse();
+        write!(f, "[")?;
+        for (i, v) in self.front.iter().chain(rear.iter()).enumerate() {
+            if i != 0 { write!(f, ", ")? }
+            write!(f, "{:?}", v)?;
+        }
+        write!(f, "]")
+    }
+}
@@ -1,4 +1,8 @@
-use super::{Stack, List, Heap};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::fmt;
+
+use super::{Stack, List, Heap, OrderedHeap, SizedHeap, Compare, Natural, SortedIter, Validate, InvariantError};
 use super::List::*;
 
 #[derive(Debug)]
@@ -23,11 +27,30 @@ impl <T> Clone for BinominalTree<T>
 }
 
 impl <T> BinominalTree<T>
-    where T: Ord + Clone
+    where T: Clone
 {
-    pub fn link(&self, that: &BinominalTree<T>) -> BinominalTree<T> {
+    pub fn singleton(node: T) -> BinominalTree<T> {
+        BinominalTree {rank: 0, node, sub: List::Nil}
+    }
+
+    pub fn rank(&self) -> i32 {
+        self.rank
+    }
+
+    pub fn node(&self) -> &T {
+        &self.node
+    }
+
+    // 子は階数の降順に並んでいる
+    pub fn sub(&self) -> &List<BinominalTree<T>> {
+        &self.sub
+    }
+
+    pub fn link_by<C>(&self, that: &BinominalTree<T>) -> BinominalTree<T>
+        where C: Compare<T>
+    {
         assert_eq!(self.rank, that.rank);
-        if self.node < that.node {
+        if C::lt(&self.node, &that.node) {
             BinominalTree {
                 rank: self.rank + 1,
                 node: self.node.clone(),
@@ -41,13 +64,46 @@ impl <T> BinominalTree<T>
             }
         }
     }
+}
+
+impl <T> BinominalTree<T>
+    where T: Ord + Clone
+{
+    pub fn link(&self, that: &BinominalTree<T>) -> BinominalTree<T> {
+        self.link_by::<Natural>(that)
+    }
 
 }
 
-pub type BHeap<T> = List<BinominalTree<T>>;
+// 木のリストは階数の昇順
+type Trees<T> = List<BinominalTree<T>>;
 
-fn ins_tree<T>(t: BinominalTree<T>, ts: &List<BinominalTree<T>>) -> BHeap<T>
-    where T: Ord + Clone
+// C で順序を与える二項ヒープ。2 番目の要素はヒープの要素数
+pub struct BHeapBy<T, C>(Trees<T>, usize, PhantomData<C>)
+    where T: Clone;
+
+// 既定の順序の二項ヒープ。型引数の既定値は式の型推論に効かないので別名にする
+pub type BHeap<T> = BHeapBy<T, Natural>;
+
+impl <T, C> Clone for BHeapBy<T, C>
+    where T: Clone
+{
+    fn clone(&self) -> BHeapBy<T, C> {
+        BHeapBy(self.0.clone(), self.1, PhantomData)
+    }
+}
+
+impl <T, C> fmt::Debug for BHeapBy<T, C>
+    where T: fmt::Debug + Clone
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BHeap({:?})", self.0)
+    }
+}
+
+fn ins_tree<T, C>(t: BinominalTree<T>, ts: &Trees<T>) -> Trees<T>
+    where T: Clone,
+          C: Compare<T>
 {
     match ts {
         &Nil => List::singleton(t.clone()),
@@ -56,14 +112,38 @@ fn ins_tree<T>(t: BinominalTree<T>, ts: &List<BinominalTree<T>>) -> BHeap<T>
             if t.rank < x.rank {
                 ts.snoc(t)
             } else {
-                ins_tree(t.link(x), xs)
+                ins_tree::<T, C>(t.link_by::<C>(x), xs)
             }
         }
     }
 }
 
-fn remove_min_tree<T>(ts: &BHeap<T>) -> (&BinominalTree<T>, BHeap<T>)
-    where T: Ord + Clone
+fn merge_trees<T, C>(ts1: &Trees<T>, ts2: &Trees<T>) -> Trees<T>
+    where T: Clone,
+          C: Compare<T>
+{
+    match (ts1, ts2) {
+        (ts, &Nil) => ts.clone(),
+        (&Nil, ts) => ts.clone(),
+        (&Cons(ref rts1), &Cons(ref rts2)) => {
+            let (t1, ts1_) = (&rts1.0, &rts1.1);
+            let (t2, ts2_) = (&rts2.0, &rts2.1);
+            if t1.rank < t2.rank {
+                merge_trees::<T, C>(ts1_, ts2).snoc(t1.clone())
+            } else if t1.rank > t2.rank {
+                merge_trees::<T, C>(ts1, ts2_).snoc(t2.clone())
+            } else {
+                let linked = t1.link_by::<C>(t2);
+                let merged = merge_trees::<T, C>(ts1_, ts2_);
+                ins_tree::<T, C>(linked, &merged)
+            }
+        }
+    }
+}
+
+fn remove_min_tree<T, C>(ts: &Trees<T>) -> (&BinominalTree<T>, Trees<T>)
+    where T: Clone,
+          C: Compare<T>
 {
     if Stack::is_empty(ts) {
         panic!("remove from empty tree");
@@ -72,8 +152,8 @@ fn remove_min_tree<T>(ts: &BHeap<T>) -> (&BinominalTree<T>, BHeap<T>)
         if Stack::is_empty(xs) {
             (x, Nil)
         } else {
-            let (y, ys) = remove_min_tree(xs);
-            if x.node < y.node {
+            let (y, ys) = remove_min_tree::<T, C>(xs);
+            if C::lt(&x.node, &y.node) {
                 (x, xs.clone())
             } else {
                 (y, ys.snoc(x.clone()))
@@ -99,71 +179,149 @@ fn remove_min_tree<T>(ts: &BHeap<T>) -> (&BinominalTree<T>, BHeap<T>)
     // }
 }
 
-impl <T> BHeap<T>
-    where T: Ord + Clone
+impl <T, C> BHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
 {
     #[allow(dead_code)]
     fn find_min_naive(&self) -> &T {
-        &remove_min_tree(self).0.node
+        &remove_min_tree::<T, C>(&self.0).0.node
     }
 }
 
-impl <T> Heap<T> for BHeap<T>
-    where T: Ord + Clone + ::std::fmt::Debug
+impl <T, C> Heap<T> for BHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
 {
-    fn empty() -> BHeap<T> {
-        Stack::empty()
+    fn empty() -> BHeapBy<T, C> {
+        BHeapBy(Nil, 0, PhantomData)
     }
     fn is_empty(&self) -> bool {
-        Stack::is_empty(self)
-    }
-
-    fn insert(&self, x: T) -> BHeap<T> {
-        let t = BinominalTree {
-            rank: 0,
-            node: x,
-            sub: List::Nil
-        };
-        ins_tree(t, &self)
-    }
-
-    fn merge(&self, other: &BHeap<T>) -> BHeap<T> {
-        match (self, other) {
-            (ts, &Nil) => ts.clone(),
-            (&Nil, ts) => ts.clone(),
-            (&Cons(ref rts1), &Cons(ref rts2)) => {
-                let (t1, ts1) = (&rts1.0, &rts1.1);
-                let (t2, ts2) = (&rts2.0, &rts2.1);
-                if t1.rank < t2.rank {
-                    ts1.merge(other).snoc(t1.clone())
-                } else if t1.rank > t2.rank {
-                    self.merge(ts2).snoc(t2.clone())
-                } else {
-                    let linked = t1.link(t2);
-                    let merged = ts1.merge(ts2);
-                    ins_tree(linked, &merged)
-                }
-            }
-        }
+        Stack::is_empty(&self.0)
+    }
+
+    fn insert(&self, x: T) -> BHeapBy<T, C> {
+        BHeapBy(ins_tree::<T, C>(BinominalTree::singleton(x), &self.0), self.1 + 1, PhantomData)
+    }
+
+    fn merge(&self, other: &BHeapBy<T, C>) -> BHeapBy<T, C> {
+        BHeapBy(merge_trees::<T, C>(&self.0, &other.0), self.1 + other.1, PhantomData)
     }
 
     fn find_min(&self) -> &T {
-        if Stack::is_empty(self) {
-            panic!("tree is empty!");
-        } else {
-            let (x, xs) = self.decom();
-            if Stack::is_empty(xs) {
-                &x.node
-            } else {
-                let y = xs.find_min();
-                ::std::cmp::min(&x.node, y)
-            }
+        self.0.iter()
+            .map(|t| &t.node)
+            .fold(None, |m, x| match m {
+                None => Some(x),
+                Some(m) => Some(C::min(m, x))
+            })
+            .expect("tree is empty!")
+    }
+
+    fn delete_min(&self) -> BHeapBy<T, C> {
+        let (x, xs) = remove_min_tree::<T, C>(&self.0);
+        BHeapBy(merge_trees::<T, C>(&x.sub.reverse(), &xs), self.1 - 1, PhantomData)
+    }
+
+    // remove_min_tree を 1 回だけ呼んで、最小要素と残りを同時に得る
+    fn pop(&self) -> Option<(T, BHeapBy<T, C>)> {
+        if Stack::is_empty(&self.0) {
+            return None
         }
+        let (x, xs) = remove_min_tree::<T, C>(&self.0);
+        Some((x.node.clone(), BHeapBy(merge_trees::<T, C>(&x.sub.reverse(), &xs), self.1 - 1, PhantomData)))
+    }
+
+    // insert は償却 O(1) なので、順に insert するだけで O(n) になる
+    fn from_iter<I>(iter: I) -> BHeapBy<T, C>
+        where I: IntoIterator<Item = T>
+    {
+        iter.into_iter().fold(Heap::empty(), |h: BHeapBy<T, C>, x| h.insert(x))
+    }
+
+}
+
+impl <T, C> OrderedHeap<T> for BHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
+{
+    type Order = C;
+}
+
+impl <T, C> SizedHeap<T> for BHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
+{
+    fn len(&self) -> usize {
+        self.1
     }
+}
 
-    fn delete_min(&self) -> BHeap<T> {
-        let (x, xs) = remove_min_tree(self);
-        x.sub.reverse().merge(&xs)
+impl <T, C> FromIterator<T> for BHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
+{
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> BHeapBy<T, C> {
+        <BHeapBy<T, C> as Heap<T>>::from_iter(iter)
     }
+}
 
+impl <T, C> IntoIterator for BHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
+{
+    type Item = T;
+    type IntoIter = SortedIter<BHeapBy<T, C>, T>;
+    fn into_iter(self) -> SortedIter<BHeapBy<T, C>, T> {
+        self.into_sorted_iter()
+    }
+}
+
+// 木の要素数を返す。子は階数 r - 1, ..., 0 の順に並んでいなければならない
+fn check_tree<T, C>(t: &BinominalTree<T>) -> Result<usize, InvariantError>
+    where T: fmt::Debug + Clone,
+          C: Compare<T>
+{
+    let mut size = 1;
+    let mut expected = t.rank;
+    for c in t.sub.iter() {
+        expected -= 1;
+        if c.rank != expected {
+            return Err(InvariantError::Rank(format!("child {:?} of {:?} has rank {} but {} is expected.", c.node, t.node, c.rank, expected)))
+        }
+        if !C::le(&t.node, &c.node) {
+            return Err(InvariantError::HeapOrder(format!("child {:?} comes before parent {:?}.", c.node, t.node)))
+        }
+        size += check_tree::<T, C>(c)?;
+    }
+    if expected != 0 {
+        return Err(InvariantError::Rank(format!("tree {:?} of rank {} has {} children.", t.node, t.rank, t.rank - expected)))
+    }
+    if size != 1 << t.rank {
+        return Err(InvariantError::Size(format!("tree {:?} of rank {} has {} elements.", t.node, t.rank, size)))
+    }
+    Ok(size)
+}
+
+impl <T, C> Validate for BHeapBy<T, C>
+    where T: fmt::Debug + Clone,
+          C: Compare<T>
+{
+    fn check_invariants(&self) -> Result<(), InvariantError> {
+        let mut prev = None;
+        let mut size = 0;
+        for t in self.0.iter() {
+            if let Some(r) = prev {
+                if t.rank <= r {
+                    return Err(InvariantError::Rank(format!("rank {} follows rank {}.", t.rank, r)))
+                }
+            }
+            prev = Some(t.rank);
+            size += check_tree::<T, C>(t)?;
+        }
+        if size != self.1 {
+            return Err(InvariantError::Size(format!("heap records {} elements but has {}.", self.1, size)))
+        }
+        Ok(())
+    }
 }
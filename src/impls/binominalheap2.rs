@@ -1,4 +1,8 @@
-use super::{Stack, List, Heap};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::fmt;
+
+use super::{Stack, List, Heap, OrderedHeap, SizedHeap, Compare, Natural, SortedIter, Validate, InvariantError};
 use super::List::*;
 
 #[derive(Debug)]
@@ -25,10 +29,12 @@ impl <T> Clone for BinominalTree<T>
 }
 
 impl <T> BinominalTree<T>
-    where T: Ord + Clone
+    where T: Clone
 {
-    pub fn link(&self, that: &BinominalTree<T>) -> BinominalTree<T> {
-        if self.node < that.node {
+    pub fn link_by<C>(&self, that: &BinominalTree<T>) -> BinominalTree<T>
+        where C: Compare<T>
+    {
+        if C::lt(&self.node, &that.node) {
             BinominalTree {
                 node: self.node.clone(),
                 sub: self.sub.snoc(that.clone())
@@ -40,13 +46,46 @@ impl <T> BinominalTree<T>
             }
         }
     }
+}
+
+impl <T> BinominalTree<T>
+    where T: Ord + Clone
+{
+    pub fn link(&self, that: &BinominalTree<T>) -> BinominalTree<T> {
+        self.link_by::<Natural>(that)
+    }
 
 }
 
-pub type BHeap<T> = (List<(i32, BinominalTree<T>)>);
+// 木のリストは階数の昇順
+type Trees<T> = List<(i32, BinominalTree<T>)>;
 
-fn ins_tree<T>(rank: i32, t: BinominalTree<T>, ts: &BHeap<T>) -> BHeap<T>
-    where T: Ord + Clone
+// C で順序を与える二項ヒープ。2 番目の要素はヒープの要素数
+pub struct BHeapBy<T, C>(Trees<T>, usize, PhantomData<C>)
+    where T: Clone;
+
+// 既定の順序の二項ヒープ。型引数の既定値は式の型推論に効かないので別名にする
+pub type BHeap<T> = BHeapBy<T, Natural>;
+
+impl <T, C> Clone for BHeapBy<T, C>
+    where T: Clone
+{
+    fn clone(&self) -> BHeapBy<T, C> {
+        BHeapBy(self.0.clone(), self.1, PhantomData)
+    }
+}
+
+impl <T, C> fmt::Debug for BHeapBy<T, C>
+    where T: fmt::Debug + Clone
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BHeap({:?})", self.0)
+    }
+}
+
+fn ins_tree<T, C>(rank: i32, t: BinominalTree<T>, ts: &Trees<T>) -> Trees<T>
+    where T: Clone,
+          C: Compare<T>
 {
     match ts {
         &Nil => List::singleton((rank, t.clone())),
@@ -55,14 +94,38 @@ fn ins_tree<T>(rank: i32, t: BinominalTree<T>, ts: &BHeap<T>) -> BHeap<T>
             if rank < rank_ {
                 ts.snoc((rank, t))
             } else {
-                ins_tree(rank + 1, t.link(x), xs)
+                ins_tree::<T, C>(rank + 1, t.link_by::<C>(x), xs)
             }
         }
     }
 }
 
-fn remove_min_tree<T>(ts: &BHeap<T>) -> (&BinominalTree<T>, BHeap<T>)
-    where T: Ord + Clone
+fn merge_trees<T, C>(ts1: &Trees<T>, ts2: &Trees<T>) -> Trees<T>
+    where T: Clone,
+          C: Compare<T>
+{
+    match (ts1, ts2) {
+        (ts, &Nil) => ts.clone(),
+        (&Nil, ts) => ts.clone(),
+        (&Cons(ref rts1), &Cons(ref rts2)) => {
+            let (&(rank1, ref t1), ts1_) = (&rts1.0, &rts1.1);
+            let (&(rank2, ref t2), ts2_) = (&rts2.0, &rts2.1);
+            if rank1 < rank2 {
+                merge_trees::<T, C>(ts1_, ts2).snoc((rank1, t1.clone()))
+            } else if rank1 > rank2 {
+                merge_trees::<T, C>(ts1, ts2_).snoc((rank2, t2.clone()))
+            } else {
+                let linked = t1.link_by::<C>(t2);
+                let merged = merge_trees::<T, C>(ts1_, ts2_);
+                ins_tree::<T, C>(rank1 + 1, linked, &merged)
+            }
+        }
+    }
+}
+
+fn remove_min_tree<T, C>(ts: &Trees<T>) -> (&BinominalTree<T>, Trees<T>)
+    where T: Clone,
+          C: Compare<T>
 {
     if Stack::is_empty(ts) {
         panic!("remove from empty tree");
@@ -71,8 +134,8 @@ fn remove_min_tree<T>(ts: &BHeap<T>) -> (&BinominalTree<T>, BHeap<T>)
         if Stack::is_empty(xs) {
             (x, Nil)
         } else {
-            let (y, ys) = remove_min_tree(xs);
-            if x.node < y.node {
+            let (y, ys) = remove_min_tree::<T, C>(xs);
+            if C::lt(&x.node, &y.node) {
                 (x, xs.clone())
             } else {
                 (y, ys.snoc((rank, x.clone())))
@@ -98,66 +161,150 @@ fn remove_min_tree<T>(ts: &BHeap<T>) -> (&BinominalTree<T>, BHeap<T>)
     // }
 }
 
-impl <T> Heap<T> for BHeap<T>
-    where T: Ord + Clone
+// 子は階数の降順に並んでいるので、前から積むと昇順になる。
+// 先頭の子の階数は子の数 - 1
+fn children<T>(t: &BinominalTree<T>) -> Trees<T>
+    where T: Clone
 {
-    fn empty() -> BHeap<T> {
-        Stack::empty()
+    let rank = t.sub.count() as i32;
+    t.sub.iter()
+        .enumerate()
+        .fold(Nil, |ts, (i, x)| ts.snoc((rank - 1 - i as i32, x.clone())))
+}
+
+impl <T, C> Heap<T> for BHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
+{
+    fn empty() -> BHeapBy<T, C> {
+        BHeapBy(Nil, 0, PhantomData)
     }
     fn is_empty(&self) -> bool {
-        Stack::is_empty(self)
+        Stack::is_empty(&self.0)
     }
 
-    fn insert(&self, x: T) -> BHeap<T> {
+    fn insert(&self, x: T) -> BHeapBy<T, C> {
         let t = BinominalTree {
             node: x,
             sub: List::Nil
         };
-        ins_tree(0, t, &self)
-    }
-
-    fn merge(&self, other: &BHeap<T>) -> BHeap<T> {
-        match (self, other) {
-            (ts, &Nil) => ts.clone(),
-            (&Nil, ts) => ts.clone(),
-            (&Cons(ref rts1), &Cons(ref rts2)) => {
-                let (&(rank1, ref t1), ts1) = (&rts1.0, &rts1.1);
-                let (&(rank2, ref t2), ts2) = (&rts2.0, &rts2.1);
-                if rank1 < rank2 {
-                    ts1.merge(other).snoc((rank1, t1.clone()))
-                } else if rank1 > rank2 {
-                    self.merge(ts2).snoc((rank2, t2.clone()))
-                } else {
-                    let linked = t1.link(t2);
-                    let merged = ts1.merge(ts2);
-                    ins_tree(rank1 + 1, linked, &merged)
-                }
-            }
-        }
+        BHeapBy(ins_tree::<T, C>(0, t, &self.0), self.1 + 1, PhantomData)
+    }
+
+    fn merge(&self, other: &BHeapBy<T, C>) -> BHeapBy<T, C> {
+        BHeapBy(merge_trees::<T, C>(&self.0, &other.0), self.1 + other.1, PhantomData)
     }
 
     fn find_min(&self) -> &T {
-        if Stack::is_empty(self) {
-            panic!("tree is empty!");
-        } else {
-            let (&(_, ref x), xs) = self.decom();
-            if Stack::is_empty(xs) {
-                &x.node
-            } else {
-                let y = xs.find_min();
-                ::std::cmp::min(&x.node, y)
-            }
+        self.0.iter()
+            .map(|&(_, ref t)| &t.node)
+            .fold(None, |m, x| match m {
+                None => Some(x),
+                Some(m) => Some(C::min(m, x))
+            })
+            .expect("tree is empty!")
+    }
+
+    fn delete_min(&self) -> BHeapBy<T, C> {
+        let (x, xs) = remove_min_tree::<T, C>(&self.0);
+        BHeapBy(merge_trees::<T, C>(&children(x), &xs), self.1 - 1, PhantomData)
+    }
+
+    // remove_min_tree を 1 回だけ呼んで、最小要素と残りを同時に得る
+    fn pop(&self) -> Option<(T, BHeapBy<T, C>)> {
+        if Stack::is_empty(&self.0) {
+            return None
         }
+        let (x, xs) = remove_min_tree::<T, C>(&self.0);
+        Some((x.node.clone(), BHeapBy(merge_trees::<T, C>(&children(x), &xs), self.1 - 1, PhantomData)))
+    }
+
+    // insert は償却 O(1) なので、順に insert するだけで O(n) になる
+    fn from_iter<I>(iter: I) -> BHeapBy<T, C>
+        where I: IntoIterator<Item = T>
+    {
+        iter.into_iter().fold(Heap::empty(), |h: BHeapBy<T, C>, x| h.insert(x))
     }
 
-    fn delete_min(&self) -> BHeap<T> {
-        let (x, xs) = remove_min_tree(self);
-        x.sub.iter()
-            .enumerate()
-            .map(|(i, x)| (i as i32, x.clone()))
-            .collect::<BHeap<_>>()
-            .reverse()
-            .merge(&xs)
+}
+
+impl <T, C> OrderedHeap<T> for BHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
+{
+    type Order = C;
+}
+
+impl <T, C> SizedHeap<T> for BHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
+{
+    fn len(&self) -> usize {
+        self.1
     }
+}
 
+impl <T, C> FromIterator<T> for BHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
+{
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> BHeapBy<T, C> {
+        <BHeapBy<T, C> as Heap<T>>::from_iter(iter)
+    }
+}
+
+impl <T, C> IntoIterator for BHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
+{
+    type Item = T;
+    type IntoIter = SortedIter<BHeapBy<T, C>, T>;
+    fn into_iter(self) -> SortedIter<BHeapBy<T, C>, T> {
+        self.into_sorted_iter()
+    }
+}
+
+// 階数 rank の木として調べ、要素数を返す。子の階数は rank - 1, ..., 0 になる
+fn check_tree<T, C>(rank: i32, t: &BinominalTree<T>) -> Result<usize, InvariantError>
+    where T: fmt::Debug + Clone,
+          C: Compare<T>
+{
+    let children = t.sub.count() as i32;
+    if children != rank {
+        return Err(InvariantError::Rank(format!("tree {:?} of rank {} has {} children.", t.node, rank, children)))
+    }
+    let mut size = 1;
+    for (i, c) in t.sub.iter().enumerate() {
+        if !C::le(&t.node, &c.node) {
+            return Err(InvariantError::HeapOrder(format!("child {:?} comes before parent {:?}.", c.node, t.node)))
+        }
+        size += check_tree::<T, C>(rank - 1 - i as i32, c)?;
+    }
+    if size != 1 << rank {
+        return Err(InvariantError::Size(format!("tree {:?} of rank {} has {} elements.", t.node, rank, size)))
+    }
+    Ok(size)
+}
+
+impl <T, C> Validate for BHeapBy<T, C>
+    where T: fmt::Debug + Clone,
+          C: Compare<T>
+{
+    fn check_invariants(&self) -> Result<(), InvariantError> {
+        let mut prev = None;
+        let mut size = 0;
+        for &(rank, ref t) in self.0.iter() {
+            if let Some(r) = prev {
+                if rank <= r {
+                    return Err(InvariantError::Rank(format!("rank {} follows rank {}.", rank, r)))
+                }
+            }
+            prev = Some(rank);
+            size += check_tree::<T, C>(rank, t)?;
+        }
+        if size != self.1 {
+            return Err(InvariantError::Size(format!("heap records {} elements but has {}.", self.1, size)))
+        }
+        Ok(())
+    }
 }
@@ -4,10 +4,11 @@ use std::fmt;
 
 use super::Set;
 
+// 4 番目の要素は部分木の要素数
 #[derive(Clone)]
 pub enum Tree<T> {
     Empty,
-    Tree(Rc<(Tree<T>, T, Tree<T>)>)
+    Tree(Rc<(Tree<T>, T, Tree<T>, usize)>)
 }
 use self::Tree::*;
 
@@ -19,7 +20,7 @@ impl <T> Set<T> for Tree<T>
         match self {
             &Empty => false,
             &Tree(ref t) => {
-                let (ref left, ref node, ref right) = **t;
+                let (ref left, ref node, ref right, _) = **t;
                 if elem < node {
                     left.member(elem)
                 } else if elem > node {
@@ -32,19 +33,59 @@ impl <T> Set<T> for Tree<T>
     }
     fn insert(&self, elem: T) -> Tree<T> {
         match self {
-            &Empty => Tree(Rc::new((Empty, elem, Empty))),
+            &Empty => Tree(Rc::new((Empty, elem, Empty, 1))),
             &Tree(ref t) => {
-                let (ref left, ref node, ref right) = **t;
+                let (ref left, ref node, ref right, _) = **t;
                 if &elem < node {
-                    Tree(Rc::new((left.insert(elem), node.clone(), right.clone())))
+                    let left = left.insert(elem);
+                    let size = left.len() + right.len() + 1;
+                    Tree(Rc::new((left, node.clone(), right.clone(), size)))
                 } else if &elem > node {
-                    Tree(Rc::new((left.clone(), node.clone(), right.insert(elem))))
+                    let right = right.insert(elem);
+                    let size = left.len() + right.len() + 1;
+                    Tree(Rc::new((left.clone(), node.clone(), right, size)))
                 } else {
                     self.clone()
                 }
             }
         }
     }
+    // 部分木の要素数を持っているので O(1)
+    fn len(&self) -> usize {
+        match self {
+            &Empty => 0,
+            &Tree(ref t) => t.3
+        }
+    }
+}
+
+// 中間順に辿るので、要素は昇順に出てくる
+impl <T> IntoIterator for Tree<T>
+    where T: Clone
+{
+    type Item = T;
+    type IntoIter = ::std::vec::IntoIter<T>;
+    fn into_iter(self) -> ::std::vec::IntoIter<T> {
+        let mut result = Vec::new();
+        let mut stack = Vec::new();
+        let mut point = &self;
+        loop {
+            match point {
+                &Tree(ref t) => {
+                    stack.push(t);
+                    point = &t.0;
+                },
+                &Empty => match stack.pop() {
+                    Some(t) => {
+                        result.push(t.1.clone());
+                        point = &t.2;
+                    },
+                    None => break
+                }
+            }
+        }
+        result.into_iter()
+    }
 }
 
 impl <T> fmt::Debug for Tree<T>
@@ -54,7 +95,7 @@ impl <T> fmt::Debug for Tree<T>
         match self {
             &Empty => write!(f, "E"),
             &Tree(ref t) => {
-                let (ref left, ref node, ref right) = **t;
+                let (ref left, ref node, ref right, _) = **t;
                 write!(f, "T({})[{:?}, {:?}, {:?}]", Rc::strong_count(&t), node, left, right)
             }
         }
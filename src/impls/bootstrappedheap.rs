@@ -0,0 +1,251 @@
+/// 10.2.2 ヒープの構造的抽象
+use std::rc::Rc;
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::FromIterator;
+
+use super::{Heap, OrderedHeap, Natural, SortedIter};
+use super::skewbinomialheap::SkewBinomialHeap;
+use super::pairingheap::PairingHeap;
+
+// 要素の型を受け取ってヒープの型を返す、ヒープの族。
+// BootstrappedHeap は自分自身を要素とするヒープを持つので、
+// 具体的なヒープの型ではなく族をパラメータとして受け取る。
+// E: Ord を impl の条件にすると、要素の Ord を調べるときに循環するので、
+// Heap であることは使う側で要求する
+pub trait HeapOf<E> {
+    type Heap;
+
+    // 基本ヒープのうち他から共有されていない部分から要素を取り出して out に積む。
+    // drop で使うので要素の順序は要求しない。既定では何もせず、drop は再帰する
+    fn take_unshared(_h: &mut Self::Heap, _out: &mut Vec<E>) {}
+}
+
+pub struct SkewBinomial;
+
+impl <E> HeapOf<E> for SkewBinomial {
+    type Heap = SkewBinomialHeap<E>;
+
+    fn take_unshared(h: &mut SkewBinomialHeap<E>, out: &mut Vec<E>) {
+        h.take_unshared(out)
+    }
+}
+
+pub struct Pairing;
+
+impl <E> HeapOf<E> for Pairing {
+    type Heap = PairingHeap<E>;
+
+    fn take_unshared(h: &mut PairingHeap<E>, out: &mut Vec<E>) {
+        h.take_unshared(out)
+    }
+}
+
+// 基本ヒープの要素。空でないヒープの中身を包み、根の要素だけで比べる。
+// この順序はヒープとしての等しさではないので、外から名前を付けられないモジュールに置く
+mod root {
+    use std::rc::Rc;
+    use super::HeapOf;
+
+    pub struct Root<T, P>(pub Rc<(T, P::Heap)>)
+        where P: HeapOf<Root<T, P>>;
+}
+use self::root::Root;
+
+impl <T, P> Clone for Root<T, P>
+    where P: HeapOf<Root<T, P>>
+{
+    fn clone(&self) -> Root<T, P> {
+        Root(self.0.clone())
+    }
+}
+
+impl <T, P> PartialEq for Root<T, P>
+    where T: Ord,
+          P: HeapOf<Root<T, P>>
+{
+    fn eq(&self, other: &Root<T, P>) -> bool {
+        (self.0).0 == (other.0).0
+    }
+}
+
+impl <T, P> Eq for Root<T, P>
+    where T: Ord,
+          P: HeapOf<Root<T, P>>
+{}
+
+impl <T, P> PartialOrd for Root<T, P>
+    where T: Ord,
+          P: HeapOf<Root<T, P>>
+{
+    fn partial_cmp(&self, other: &Root<T, P>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl <T, P> Ord for Root<T, P>
+    where T: Ord,
+          P: HeapOf<Root<T, P>>
+{
+    fn cmp(&self, other: &Root<T, P>) -> Ordering {
+        (self.0).0.cmp(&(other.0).0)
+    }
+}
+
+impl <T, P> fmt::Debug for Root<T, P>
+    where T: fmt::Debug,
+          P: HeapOf<Root<T, P>>,
+          P::Heap: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "H({:?}, {:?})", (self.0).0, (self.0).1)
+    }
+}
+
+// 根の要素と、子のヒープを要素とする基本ヒープの組。
+// 基本ヒープの insert が O(1) なら merge も O(1) になる
+pub enum BootstrappedHeap<T, P = SkewBinomial>
+    where P: HeapOf<Root<T, P>>
+{
+    Empty,
+    Heap(Rc<(T, P::Heap)>)
+}
+use self::BootstrappedHeap::*;
+
+impl <T, P> Clone for BootstrappedHeap<T, P>
+    where P: HeapOf<Root<T, P>>
+{
+    fn clone(&self) -> BootstrappedHeap<T, P> {
+        match self {
+            &Empty => Empty,
+            &Heap(ref h) => Heap(h.clone())
+        }
+    }
+}
+
+// 降順に insert すると、新しい根が古いヒープだけを入れた基本ヒープを持つ n 段の入れ子になる。
+// 既定の drop はその深さだけ再帰するので、基本ヒープから子を取り出してから落とす
+impl <T, P> Drop for BootstrappedHeap<T, P>
+    where P: HeapOf<Root<T, P>>
+{
+    fn drop(&mut self) {
+        let mut stack = Vec::new();
+        if let Heap(ref mut h) = *self {
+            if let Some(node) = Rc::get_mut(h) {
+                P::take_unshared(&mut node.1, &mut stack);
+            }
+        }
+        while let Some(mut r) = stack.pop() {
+            if let Some(node) = Rc::get_mut(&mut r.0) {
+                P::take_unshared(&mut node.1, &mut stack);
+            }
+        }
+    }
+}
+
+impl <T, P> Heap<T> for BootstrappedHeap<T, P>
+    where T: Ord + Clone,
+          P: HeapOf<Root<T, P>>,
+          P::Heap: Heap<Root<T, P>> + Clone
+{
+    fn empty() -> BootstrappedHeap<T, P> {
+        Empty
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            &Empty => true,
+            &Heap(_) => false
+        }
+    }
+
+    fn insert(&self, x: T) -> BootstrappedHeap<T, P> {
+        Heap(Rc::new((x, <P::Heap as Heap<_>>::empty()))).merge(self)
+    }
+
+    fn merge(&self, other: &BootstrappedHeap<T, P>) -> BootstrappedHeap<T, P> {
+        match (self, other) {
+            (h, &Empty) => h.clone(),
+            (&Empty, h) => h.clone(),
+            (&Heap(ref h1), &Heap(ref h2)) => {
+                if h1.0 <= h2.0 {
+                    Heap(Rc::new((h1.0.clone(), h1.1.insert(Root(h2.clone())))))
+                } else {
+                    Heap(Rc::new((h2.0.clone(), h2.1.insert(Root(h1.clone())))))
+                }
+            }
+        }
+    }
+
+    fn find_min(&self) -> &T {
+        match self {
+            &Empty => panic!("empty heap"),
+            &Heap(ref h) => &h.0
+        }
+    }
+
+    // 基本ヒープの最小要素が次の根になり、その子のヒープを残りと merge する
+    fn delete_min(&self) -> BootstrappedHeap<T, P> {
+        match self {
+            &Empty => panic!("empty heap"),
+            &Heap(ref h) => {
+                let p = &h.1;
+                if p.is_empty() {
+                    return Empty
+                }
+                let m = &p.find_min().0;
+                Heap(Rc::new((m.0.clone(), m.1.merge(&p.delete_min()))))
+            }
+        }
+    }
+
+    // insert が O(1) なので、順に insert するだけで O(n) になる
+    fn from_iter<I>(iter: I) -> BootstrappedHeap<T, P>
+        where I: IntoIterator<Item = T>
+    {
+        iter.into_iter().fold(Empty, |h: BootstrappedHeap<T, P>, x| h.insert(x))
+    }
+}
+
+impl <T, P> OrderedHeap<T> for BootstrappedHeap<T, P>
+    where T: Ord + Clone,
+          P: HeapOf<Root<T, P>>,
+          P::Heap: Heap<Root<T, P>> + Clone
+{
+    type Order = Natural;
+}
+
+impl <T, P> FromIterator<T> for BootstrappedHeap<T, P>
+    where T: Ord + Clone,
+          P: HeapOf<Root<T, P>>,
+          P::Heap: Heap<Root<T, P>> + Clone
+{
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> BootstrappedHeap<T, P> {
+        <BootstrappedHeap<T, P> as Heap<T>>::from_iter(iter)
+    }
+}
+
+impl <T, P> IntoIterator for BootstrappedHeap<T, P>
+    where T: Ord + Clone,
+          P: HeapOf<Root<T, P>>,
+          P::Heap: Heap<Root<T, P>> + Clone
+{
+    type Item = T;
+    type IntoIter = SortedIter<BootstrappedHeap<T, P>, T>;
+    fn into_iter(self) -> SortedIter<BootstrappedHeap<T, P>, T> {
+        self.into_sorted_iter()
+    }
+}
+
+impl <T, P> fmt::Debug for BootstrappedHeap<T, P>
+    where T: fmt::Debug,
+          P: HeapOf<Root<T, P>>,
+          P::Heap: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &Empty => write!(f, "E"),
+            &Heap(ref h) => write!(f, "H({:?}, {:?})", h.0, h.1)
+        }
+    }
+}
@@ -0,0 +1,167 @@
+/// 10.1.3 ブートストラップされたキュー
+use std::rc::Rc;
+use std::fmt;
+
+use super::{Stack, List, Susp, Queue};
+
+// 中間のキューの要素は「遅延された反転済みリスト」で、その中間のキューの要素は
+// さらにそのリストになる (多相再帰)。Rust では型が無限に展開されてしまうので、
+// 各段の要素を 1 つの型にまとめて扱う。
+enum Elem<T> {
+    Leaf(T),
+    Chunk(Susp<List<Elem<T>>>)
+}
+use self::Elem::*;
+
+impl <T> Clone for Elem<T>
+    where T: Clone
+{
+    fn clone(&self) -> Elem<T> {
+        match self {
+            &Leaf(ref x) => Leaf(x.clone()),
+            &Chunk(ref s) => Chunk(s.clone())
+        }
+    }
+}
+
+// lenfm は front と middle に含まれるリストの長さの合計
+pub struct Body<T> {
+    lenfm: usize,
+    front: List<Elem<T>>,
+    middle: BootstrappedQueue<T>,
+    lenr: usize,
+    rear: List<Elem<T>>
+}
+
+pub enum BootstrappedQueue<T> {
+    Empty,
+    Queue(Rc<Body<T>>)
+}
+use self::BootstrappedQueue::*;
+
+impl <T> Clone for BootstrappedQueue<T> {
+    fn clone(&self) -> BootstrappedQueue<T> {
+        match self {
+            &Empty => Empty,
+            &Queue(ref q) => Queue(q.clone())
+        }
+    }
+}
+
+impl <T> BootstrappedQueue<T>
+    where T: Clone + 'static
+{
+    pub fn len(&self) -> usize {
+        match self {
+            &Empty => 0,
+            &Queue(ref q) => q.lenfm + q.lenr
+        }
+    }
+
+    fn snoc_elem(&self, x: Elem<T>) -> BootstrappedQueue<T> {
+        match self {
+            &Empty => Queue(Rc::new(Body {
+                lenfm: 1,
+                front: List::singleton(x),
+                middle: Empty,
+                lenr: 0,
+                rear: List::Nil
+            })),
+            &Queue(ref q) => {
+                BootstrappedQueue::check_q(q.lenfm, q.front.clone(), q.middle.clone(),
+                                           q.lenr + 1, q.rear.snoc(x))
+            }
+        }
+    }
+
+    fn head_elem(&self) -> &Elem<T> {
+        match self {
+            &Empty => panic!("empty queue"),
+            &Queue(ref q) => q.front.head()
+        }
+    }
+
+    fn tail_elem(&self) -> BootstrappedQueue<T> {
+        match self {
+            &Empty => panic!("empty queue"),
+            &Queue(ref q) => {
+                BootstrappedQueue::check_q(q.lenfm - 1, q.front.tail().clone(), q.middle.clone(),
+                                           q.lenr, q.rear.clone())
+            }
+        }
+    }
+
+    fn check_q(lenfm: usize, front: List<Elem<T>>, middle: BootstrappedQueue<T>,
+               lenr: usize, rear: List<Elem<T>>) -> BootstrappedQueue<T> {
+        if lenr <= lenfm {
+            BootstrappedQueue::check_f(lenfm, front, middle, lenr, rear)
+        } else {
+            let middle = middle.snoc_elem(Chunk(Susp::new(move || rear.reverse())));
+            BootstrappedQueue::check_f(lenfm + lenr, front, middle, 0, List::Nil)
+        }
+    }
+
+    fn check_f(lenfm: usize, front: List<Elem<T>>, middle: BootstrappedQueue<T>,
+               lenr: usize, rear: List<Elem<T>>) -> BootstrappedQueue<T> {
+        if Stack::is_empty(&front) {
+            match middle {
+                Empty => Empty,
+                Queue(_) => {
+                    let front = match middle.head_elem() {
+                        &Chunk(ref s) => s.force().clone(),
+                        &Leaf(_) => panic!("leaf in the middle queue")
+                    };
+                    let middle = middle.tail_elem();
+                    Queue(Rc::new(Body {lenfm, front, middle, lenr, rear}))
+                }
+            }
+        } else {
+            Queue(Rc::new(Body {lenfm, front, middle, lenr, rear}))
+        }
+    }
+}
+
+impl <T> Queue<T> for BootstrappedQueue<T>
+    where T: Clone + 'static
+{
+    fn empty() -> BootstrappedQueue<T> {
+        Empty
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            &Empty => true,
+            &Queue(_) => false
+        }
+    }
+
+    fn snoc(&self, x: T) -> BootstrappedQueue<T> {
+        self.snoc_elem(Leaf(x))
+    }
+
+    fn head(&self) -> &T {
+        match self.head_elem() {
+            &Leaf(ref x) => x,
+            &Chunk(_) => panic!("chunk in the top-level queue")
+        }
+    }
+
+    fn tail(&self) -> BootstrappedQueue<T> {
+        self.tail_elem()
+    }
+}
+
+impl <T> fmt::Debug for BootstrappedQueue<T>
+    where T: fmt::Debug + Clone + 'static
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut q = self.clone();
+        write!(f, "[")?;
+        while !q.is_empty() {
+            write!(f, "{:?}", q.head())?;
+            q = q.tail();
+            if !q.is_empty() { write!(f, ", ")? }
+        }
+        write!(f, "]")
+    }
+}
@@ -0,0 +1,282 @@
+/// 11.2 単純な連結可能両端キュー
+use std::rc::Rc;
+use std::fmt;
+
+use super::{Susp, Deque, CatenableDeque};
+use super::bankersdeque::BankersDeque;
+
+// 中間の連結可能両端キューの要素は両端キューで、その中間の要素は
+// さらに両端キューの両端キューになる (多相再帰)。Rust では型が無限に展開されて
+// しまうので、各段の要素を 1 つの型にまとめて扱う。
+enum Elem<T> {
+    Leaf(T),
+    Sub(BankersDeque<Elem<T>>)
+}
+use self::Elem::*;
+
+impl <T> Clone for Elem<T>
+    where T: Clone
+{
+    fn clone(&self) -> Elem<T> {
+        match self {
+            &Leaf(ref x) => Leaf(x.clone()),
+            &Sub(ref d) => Sub(d.clone())
+        }
+    }
+}
+
+type D<T> = BankersDeque<Elem<T>>;
+
+// Deep の front と rear は常に 2 要素以上
+enum Node<T> {
+    Shallow(D<T>),
+    Deep(Rc<(D<T>, Susp<Node<T>>, D<T>)>)
+}
+use self::Node::*;
+
+impl <T> Clone for Node<T>
+    where T: Clone
+{
+    fn clone(&self) -> Node<T> {
+        match self {
+            &Shallow(ref d) => Shallow(d.clone()),
+            &Deep(ref q) => Deep(q.clone())
+        }
+    }
+}
+
+fn too_small<T>(d: &D<T>) -> bool
+    where T: Clone + 'static
+{
+    d.len() < 2
+}
+
+// d1 は too_small であること
+fn dappend_l<T>(d1: &D<T>, d2: &D<T>) -> D<T>
+    where T: Clone + 'static
+{
+    if d1.is_empty() {
+        d2.clone()
+    } else {
+        dappend_l(&d1.init(), &d2.cons(d1.last().clone()))
+    }
+}
+
+// d2 は too_small であること
+fn dappend_r<T>(d1: &D<T>, d2: &D<T>) -> D<T>
+    where T: Clone + 'static
+{
+    if d2.is_empty() {
+        d1.clone()
+    } else {
+        dappend_r(&d1.snoc(d2.head().clone()), &d2.tail())
+    }
+}
+
+fn deque_of<T>(x: &Elem<T>) -> &D<T> {
+    match x {
+        &Sub(ref d) => d,
+        &Leaf(_) => panic!("leaf in the middle deque")
+    }
+}
+
+impl <T> Node<T>
+    where T: Clone + 'static
+{
+    fn is_empty(&self) -> bool {
+        match self {
+            &Shallow(ref d) => d.is_empty(),
+            &Deep(_) => false
+        }
+    }
+
+    fn cons(&self, x: Elem<T>) -> Node<T> {
+        match self {
+            &Shallow(ref d) => Shallow(d.cons(x)),
+            &Deep(ref q) => Deep(Rc::new((q.0.cons(x), q.1.clone(), q.2.clone())))
+        }
+    }
+
+    fn head(&self) -> &Elem<T> {
+        match self {
+            &Shallow(ref d) => d.head(),
+            &Deep(ref q) => q.0.head()
+        }
+    }
+
+    fn tail(&self) -> Node<T> {
+        match self {
+            &Shallow(ref d) => Shallow(d.tail()),
+            &Deep(ref q) => {
+                let (ref f, ref m, ref r) = **q;
+                let f = f.tail();
+                if !too_small(&f) {
+                    Deep(Rc::new((f, m.clone(), r.clone())))
+                } else if m.force().is_empty() {
+                    Shallow(dappend_l(&f, r))
+                } else {
+                    let inner = m.force();
+                    let f = dappend_l(&f, deque_of(inner.head()));
+                    let inner = inner.clone();
+                    Deep(Rc::new((f, Susp::new(move || inner.tail()), r.clone())))
+                }
+            }
+        }
+    }
+
+    fn snoc(&self, x: Elem<T>) -> Node<T> {
+        match self {
+            &Shallow(ref d) => Shallow(d.snoc(x)),
+            &Deep(ref q) => Deep(Rc::new((q.0.clone(), q.1.clone(), q.2.snoc(x))))
+        }
+    }
+
+    fn last(&self) -> &Elem<T> {
+        match self {
+            &Shallow(ref d) => d.last(),
+            &Deep(ref q) => q.2.last()
+        }
+    }
+
+    fn init(&self) -> Node<T> {
+        match self {
+            &Shallow(ref d) => Shallow(d.init()),
+            &Deep(ref q) => {
+                let (ref f, ref m, ref r) = **q;
+                let r = r.init();
+                if !too_small(&r) {
+                    Deep(Rc::new((f.clone(), m.clone(), r)))
+                } else if m.force().is_empty() {
+                    Shallow(dappend_r(f, &r))
+                } else {
+                    let inner = m.force();
+                    let r = dappend_r(deque_of(inner.last()), &r);
+                    let inner = inner.clone();
+                    Deep(Rc::new((f.clone(), Susp::new(move || inner.init()), r)))
+                }
+            }
+        }
+    }
+
+    fn append(&self, other: &Node<T>) -> Node<T> {
+        match (self, other) {
+            (&Shallow(ref d1), &Shallow(ref d2)) => {
+                if too_small(d1) {
+                    Shallow(dappend_l(d1, d2))
+                } else if too_small(d2) {
+                    Shallow(dappend_r(d1, d2))
+                } else {
+                    Deep(Rc::new((d1.clone(), Susp::value(Shallow(Deque::empty())), d2.clone())))
+                }
+            },
+            (&Shallow(ref d), &Deep(ref q)) => {
+                let (ref f, ref m, ref r) = **q;
+                if too_small(d) {
+                    Deep(Rc::new((dappend_l(d, f), m.clone(), r.clone())))
+                } else {
+                    // 中間への cons まで遅延すると連結のたびにサスペンションが入れ子になり、
+                    // force や drop の再帰が連結の回数だけ深くなるので、ここで評価しておく
+                    let m = m.force().cons(Sub(f.clone()));
+                    Deep(Rc::new((d.clone(), Susp::value(m), r.clone())))
+                }
+            },
+            (&Deep(ref q), &Shallow(ref d)) => {
+                let (ref f, ref m, ref r) = **q;
+                if too_small(d) {
+                    Deep(Rc::new((f.clone(), m.clone(), dappend_r(r, d))))
+                } else {
+                    let m = m.force().snoc(Sub(r.clone()));
+                    Deep(Rc::new((f.clone(), Susp::value(m), d.clone())))
+                }
+            },
+            (&Deep(ref q1), &Deep(ref q2)) => {
+                let (ref f1, ref m1, ref r1) = **q1;
+                let (ref f2, ref m2, ref r2) = **q2;
+                let (m1, r1, f2, m2) = (m1.clone(), r1.clone(), f2.clone(), m2.clone());
+                let m = Susp::new(move || {
+                    m1.force().snoc(Sub(r1)).append(&m2.force().cons(Sub(f2)))
+                });
+                Deep(Rc::new((f1.clone(), m, r2.clone())))
+            }
+        }
+    }
+}
+
+// cons, snoc, head, last, append は償却 O(1)。
+// tail と init は中間の両端キューを 1 段ずつ辿るので償却 O(log n)。
+// すべての操作を償却 O(1) にしたものは implicitcatenabledeque::ImplicitCatenableDeque
+pub struct SimpleCatenableDeque<T>(Node<T>);
+
+impl <T> Clone for SimpleCatenableDeque<T>
+    where T: Clone
+{
+    fn clone(&self) -> SimpleCatenableDeque<T> {
+        SimpleCatenableDeque(self.0.clone())
+    }
+}
+
+fn leaf<T>(x: &Elem<T>) -> &T {
+    match x {
+        &Leaf(ref x) => x,
+        &Sub(_) => panic!("sub-deque in the top-level deque")
+    }
+}
+
+impl <T> Deque<T> for SimpleCatenableDeque<T>
+    where T: Clone + 'static
+{
+    fn empty() -> SimpleCatenableDeque<T> {
+        SimpleCatenableDeque(Shallow(Deque::empty()))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn cons(&self, x: T) -> SimpleCatenableDeque<T> {
+        SimpleCatenableDeque(self.0.cons(Leaf(x)))
+    }
+
+    fn head(&self) -> &T {
+        leaf(self.0.head())
+    }
+
+    fn tail(&self) -> SimpleCatenableDeque<T> {
+        SimpleCatenableDeque(self.0.tail())
+    }
+
+    fn snoc(&self, x: T) -> SimpleCatenableDeque<T> {
+        SimpleCatenableDeque(self.0.snoc(Leaf(x)))
+    }
+
+    fn last(&self) -> &T {
+        leaf(self.0.last())
+    }
+
+    fn init(&self) -> SimpleCatenableDeque<T> {
+        SimpleCatenableDeque(self.0.init())
+    }
+}
+
+impl <T> CatenableDeque<T> for SimpleCatenableDeque<T>
+    where T: Clone + 'static
+{
+    fn append(&self, other: &SimpleCatenableDeque<T>) -> SimpleCatenableDeque<T> {
+        SimpleCatenableDeque(self.0.append(&other.0))
+    }
+}
+
+impl <T> fmt::Debug for SimpleCatenableDeque<T>
+    where T: fmt::Debug + Clone + 'static
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut d = self.clone();
+        write!(f, "[")?;
+        while !d.is_empty() {
+            write!(f, "{:?}", d.head())?;
+            d = d.tail();
+            if !d.is_empty() { write!(f, ", ")? }
+        }
+        write!(f, "]")
+    }
+}
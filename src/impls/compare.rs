@@ -0,0 +1,61 @@
+/// 要素の順序付け
+// ヒープの型パラメータとして渡す比較の戦略。
+// インスタンスを持たず関数だけなので、永続データ構造の節点に何も追加しなくて済む
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+
+pub trait Compare<T> {
+    fn compare(a: &T, b: &T) -> Ordering;
+
+    fn lt(a: &T, b: &T) -> bool {
+        Self::compare(a, b) == Ordering::Less
+    }
+
+    fn le(a: &T, b: &T) -> bool {
+        Self::compare(a, b) != Ordering::Greater
+    }
+
+    // 等しいときは a を返す
+    fn min<'a>(a: &'a T, b: &'a T) -> &'a T {
+        if Self::le(a, b) { a } else { b }
+    }
+}
+
+// T: Ord の順序そのまま。最小ヒープになる
+pub struct Natural;
+
+impl <T> Compare<T> for Natural
+    where T: Ord
+{
+    fn compare(a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+// C の逆順。Reversed<Natural> で最大ヒープになる
+pub struct Reversed<C = Natural>(PhantomData<C>);
+
+impl <T, C> Compare<T> for Reversed<C>
+    where C: Compare<T>
+{
+    fn compare(a: &T, b: &T) -> Ordering {
+        C::compare(b, a)
+    }
+}
+
+// 要素から優先度を取り出す関数
+pub trait KeyFn<T> {
+    type Key: Ord;
+    fn key(x: &T) -> Self::Key;
+}
+
+// K で取り出した優先度の順
+pub struct ByKey<K>(PhantomData<K>);
+
+impl <T, K> Compare<T> for ByKey<K>
+    where K: KeyFn<T>
+{
+    fn compare(a: &T, b: &T) -> Ordering {
+        K::key(a).cmp(&K::key(b))
+    }
+}
@@ -0,0 +1,20 @@
+pub trait Deque<T>
+    where T: Clone
+{
+    fn empty() -> Self;
+    fn is_empty(&self) -> bool;
+
+    fn cons(&self, x: T) -> Self;
+    fn head(&self) -> &T; // panic if the deque is empty.
+    fn tail(&self) -> Self; // panic if the deque is empty.
+
+    fn snoc(&self, x: T) -> Self;
+    fn last(&self) -> &T; // panic if the deque is empty.
+    fn init(&self) -> Self; // panic if the deque is empty.
+}
+
+pub trait CatenableDeque<T>: Deque<T>
+    where T: Clone
+{
+    fn append(&self, other: &Self) -> Self;
+}
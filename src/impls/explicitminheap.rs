@@ -1,12 +1,16 @@
-use ::std::cmp;
+/// 演習 3.7 最小要素を別に持つことで、どのヒープでも find_min を O(1) にする
+use ::std::iter::FromIterator;
+use ::std::cmp::Ordering;
+use ::std::fmt;
 
-use super::Heap;
+use super::{Heap, OrderedHeap, SizedHeap, Compare, SortedIter, Validate, InvariantError};
 
-enum ExplicitMinHeap<H, T>
+// 最小要素は H と同じ順序 H::Order で選ぶ。Node の 3 番目の要素は要素数
+pub enum ExplicitMinHeap<H, T>
     where H: Heap<T>
 {
     Empty,
-    Node(T, H)
+    Node(T, H, usize)
 }
 
 impl <H, T> Clone for ExplicitMinHeap<H, T>
@@ -16,16 +20,54 @@ impl <H, T> Clone for ExplicitMinHeap<H, T>
     fn clone(&self) -> ExplicitMinHeap<H, T> {
         match self {
             &Empty => Empty,
-            &Node(ref min, ref h) => Node(min.clone(), h.clone())
+            &Node(ref min, ref h, size) => Node(min.clone(), h.clone(), size)
         }
     }
 }
 
 use self::ExplicitMinHeap::*;
 
+impl <H, T> fmt::Debug for ExplicitMinHeap<H, T>
+    where H: Heap<T> + fmt::Debug,
+          T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &Empty => write!(f, "E"),
+            &Node(ref min, ref h, _) => write!(f, "ExplicitMinHeap({:?}, {:?})", min, h)
+        }
+    }
+}
+
+impl <H, T> ExplicitMinHeap<H, T>
+    where H: Heap<T>,
+          T: Clone
+{
+    // 既存のヒープを包む。要素数は H の len で O(1) で得る
+    pub fn new(h: H) -> ExplicitMinHeap<H, T>
+        where H: SizedHeap<T>
+    {
+        let min = h.try_find_min().cloned();
+        match min {
+            None => Empty,
+            Some(min) => {
+                let size = h.len();
+                Node(min, h, size)
+            }
+        }
+    }
+
+    pub fn into_inner(self) -> H {
+        match self {
+            Empty => H::empty(),
+            Node(_, h, _) => h
+        }
+    }
+}
+
 impl <H, T> Heap<T> for ExplicitMinHeap<H, T>
-    where H: Heap<T> + Clone,
-          T: Ord + Clone
+    where H: OrderedHeap<T> + Clone,
+          T: Clone
 {
     fn empty() -> ExplicitMinHeap<H, T> {
         Empty
@@ -34,7 +76,7 @@ impl <H, T> Heap<T> for ExplicitMinHeap<H, T>
     fn is_empty(&self) -> bool {
         match self {
             &Empty => true,
-            &Node(_, _) => false
+            &Node(_, _, _) => false
         }
     }
 
@@ -42,12 +84,12 @@ impl <H, T> Heap<T> for ExplicitMinHeap<H, T>
         match self {
             &Empty => {
                 let h = H::empty().insert(node.clone());
-                Node(node, h)
+                Node(node, h, 1)
             },
-            &Node(ref min, ref h) => {
-                let min = cmp::min(min, &node).clone();
+            &Node(ref min, ref h, size) => {
+                let min = H::Order::min(min, &node).clone();
                 let h = h.insert(node);
-                Node(min, h)
+                Node(min, h, size + 1)
             }
         }
     }
@@ -56,10 +98,10 @@ impl <H, T> Heap<T> for ExplicitMinHeap<H, T>
         match (self, other) {
             (ts, &Empty) => ts.clone(),
             (&Empty, ts) => ts.clone(),
-            (&Node(ref m1, ref h1), &Node(ref m2, ref h2)) => {
-                let min = cmp::min(m1, m2).clone();
+            (&Node(ref m1, ref h1, s1), &Node(ref m2, ref h2, s2)) => {
+                let min = H::Order::min(m1, m2).clone();
                 let h = h1.merge(h2);
-                Node(min, h)
+                Node(min, h, s1 + s2)
             }
         }
     }
@@ -67,19 +109,82 @@ impl <H, T> Heap<T> for ExplicitMinHeap<H, T>
     fn find_min(&self) -> &T {
         match self {
             &Empty => panic!("find_min called for empty tree"),
-            &Node(ref min, _) => min
+            &Node(ref min, _, _) => min
         }
     }
 
     fn delete_min(&self) -> ExplicitMinHeap<H, T> {
         match self {
             &Empty => panic!("delete_min called for empty tree"),
-            &Node(_, ref h) => {
-                let min = h.find_min().clone();
+            &Node(_, ref h, size) => {
                 let h = h.delete_min();
-                Node(min, h)
+                let min = h.try_find_min().cloned();
+                match min {
+                    None => Empty,
+                    Some(min) => Node(min, h, size - 1)
+                }
             }
         }
     }
+}
+
+impl <H, T> OrderedHeap<T> for ExplicitMinHeap<H, T>
+    where H: OrderedHeap<T> + Clone,
+          T: Clone
+{
+    type Order = H::Order;
+}
+
+impl <H, T> SizedHeap<T> for ExplicitMinHeap<H, T>
+    where H: OrderedHeap<T> + Clone,
+          T: Clone
+{
+    fn len(&self) -> usize {
+        match self {
+            &Empty => 0,
+            &Node(_, _, size) => size
+        }
+    }
+}
 
+impl <H, T> FromIterator<T> for ExplicitMinHeap<H, T>
+    where H: OrderedHeap<T> + Clone,
+          T: Clone
+{
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> ExplicitMinHeap<H, T> {
+        <ExplicitMinHeap<H, T> as Heap<T>>::from_iter(iter)
+    }
+}
+
+impl <H, T> IntoIterator for ExplicitMinHeap<H, T>
+    where H: OrderedHeap<T> + Clone,
+          T: Clone
+{
+    type Item = T;
+    type IntoIter = SortedIter<ExplicitMinHeap<H, T>, T>;
+    fn into_iter(self) -> SortedIter<ExplicitMinHeap<H, T>, T> {
+        self.into_sorted_iter()
+    }
+}
+
+// 中のヒープ自体の不変条件も調べる
+impl <H, T> Validate for ExplicitMinHeap<H, T>
+    where H: OrderedHeap<T> + Validate,
+          T: fmt::Debug
+{
+    fn check_invariants(&self) -> Result<(), InvariantError> {
+        match self {
+            &Empty => Ok(()),
+            &Node(ref min, ref h, _) => {
+                h.check_invariants()?;
+                match h.try_find_min() {
+                    None => Err(InvariantError::CachedMin(format!("cached min {:?} but the heap is empty.", min))),
+                    Some(m) if H::Order::compare(min, m) != Ordering::Equal => {
+                        Err(InvariantError::CachedMin(format!("cached min {:?} but the heap min is {:?}.", min, m)))
+                    },
+                    Some(_) => Ok(())
+                }
+            }
+        }
+    }
 }
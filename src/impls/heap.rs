@@ -1,8 +1,124 @@
+use std::marker::PhantomData;
+
+use super::Compare;
+
 pub trait Heap<T> {
     fn empty() -> Self;
     fn is_empty(&self) -> bool;
     fn insert(&self, node: T) -> Self;
     fn merge(&self, other: &Self) -> Self;
-    fn find_min(&self) -> &T;
-    fn delete_min(&self) -> Self;
+    fn find_min(&self) -> &T; // panic if the heap is empty.
+    fn delete_min(&self) -> Self; // panic if the heap is empty.
+
+    fn try_find_min(&self) -> Option<&T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.find_min())
+        }
+    }
+
+    fn try_delete_min(&self) -> Option<Self>
+        where Self: Sized
+    {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.delete_min())
+        }
+    }
+
+    // 演習 3.3 要素 1 つのヒープを作ってから、隣同士を merge することを
+    // 1 つになるまで繰り返す。左偏ヒープなら O(n)
+    fn from_iter<I>(iter: I) -> Self
+        where I: IntoIterator<Item = T>,
+              Self: Sized
+    {
+        let mut hs = iter.into_iter().map(|x| Self::empty().insert(x)).collect::<Vec<_>>();
+        while hs.len() > 1 {
+            let mut merged = Vec::with_capacity((hs.len() + 1) / 2);
+            let mut iter = hs.into_iter();
+            loop {
+                match (iter.next(), iter.next()) {
+                    (Some(h1), Some(h2)) => merged.push(h1.merge(&h2)),
+                    (Some(h), None) => {
+                        merged.push(h);
+                        break
+                    },
+                    _ => break
+                }
+            }
+            hs = merged;
+        }
+        hs.pop().unwrap_or_else(Self::empty)
+    }
+
+    // 昇順に要素を取り出すイテレータ。ヒープは消費される
+    fn into_sorted_iter(self) -> SortedIter<Self, T>
+        where T: Clone,
+              Self: Sized
+    {
+        SortedIter {heap: self, element: PhantomData}
+    }
+
+    // 永続的なヒープの複製は O(1) なので、複製から取り出せば元のヒープはそのまま残る
+    fn iter_sorted(&self) -> SortedIter<Self, T>
+        where T: Clone,
+              Self: Sized + Clone
+    {
+        self.clone().into_sorted_iter()
+    }
+
+    // 最小要素と、それを取り除いたヒープを同時に返す
+    fn pop(&self) -> Option<(T, Self)>
+        where T: Clone,
+              Self: Sized
+    {
+        if self.is_empty() {
+            None
+        } else {
+            Some((self.find_min().clone(), self.delete_min()))
+        }
+    }
+}
+
+// 要素数を持っていて、len が O(1) のヒープ
+pub trait SizedHeap<T>: Heap<T> {
+    fn len(&self) -> usize;
+}
+
+// 順序を型で持つヒープ。find_min は Order での最小要素を返す。
+// ExplicitMinHeap が中のヒープと同じ順序で最小要素を選ぶのに使う
+pub trait OrderedHeap<T>: Heap<T> {
+    type Order: Compare<T>;
+}
+
+pub struct SortedIter<H, T> {
+    heap: H,
+    element: PhantomData<T>
+}
+
+impl <H, T> Iterator for SortedIter<H, T>
+    where H: Heap<T>,
+          T: Clone
+{
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        match self.heap.pop() {
+            None => None,
+            Some((x, h)) => {
+                self.heap = h;
+                Some(x)
+            }
+        }
+    }
+}
+
+// H に集めてから昇順に取り出す
+pub fn heap_sort<H, T, I>(xs: I) -> Vec<T>
+    where H: Heap<T>,
+          T: Clone,
+          I: IntoIterator<Item = T>
+{
+    H::from_iter(xs).into_sorted_iter().collect()
 }
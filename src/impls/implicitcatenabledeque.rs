@@ -0,0 +1,350 @@
+/// 11.4 暗黙の連結可能両端キュー
+use std::rc::Rc;
+use std::fmt;
+
+use super::{Susp, Deque, CatenableDeque};
+use super::bankersdeque::BankersDeque;
+
+// 最上段の要素は Leaf、下の段の要素は複合要素 Simple か Compound になる。
+// SimpleCatenableDeque と同じく、多相再帰を避けるために 1 つの型にまとめる
+enum Elem<T> {
+    Leaf(T),
+    Simple(D<T>),
+    Compound(Rc<(D<T>, Susp<Cat<T>>, D<T>)>)
+}
+use self::Elem::*;
+
+impl <T> Clone for Elem<T>
+    where T: Clone
+{
+    fn clone(&self) -> Elem<T> {
+        match self {
+            &Leaf(ref x) => Leaf(x.clone()),
+            &Simple(ref d) => Simple(d.clone()),
+            &Compound(ref c) => Compound(c.clone())
+        }
+    }
+}
+
+type D<T> = BankersDeque<Elem<T>>;
+
+// Deep(f, a, m, b, r) の f と r は 3 要素以上、m は 2 要素以上。
+// Simple の両端キューと、Compound の両端の両端キューは 2 要素以上
+enum Cat<T> {
+    Shallow(D<T>),
+    Deep(Rc<(D<T>, Susp<Cat<T>>, D<T>, Susp<Cat<T>>, D<T>)>)
+}
+use self::Cat::*;
+
+impl <T> Clone for Cat<T>
+    where T: Clone
+{
+    fn clone(&self) -> Cat<T> {
+        match self {
+            &Shallow(ref d) => Shallow(d.clone()),
+            &Deep(ref q) => Deep(q.clone())
+        }
+    }
+}
+
+fn deep<T>(f: D<T>, a: Susp<Cat<T>>, m: D<T>, b: Susp<Cat<T>>, r: D<T>) -> Cat<T> {
+    Deep(Rc::new((f, a, m, b, r)))
+}
+
+// 両端キューを要素ごとに移して連結する。d1 は小さいこと
+fn dappend_l<T>(d1: &D<T>, d2: &D<T>) -> D<T>
+    where T: Clone + 'static
+{
+    if d1.is_empty() {
+        d2.clone()
+    } else {
+        dappend_l(&d1.init(), &d2.cons(d1.last().clone()))
+    }
+}
+
+// d2 は小さいこと
+fn dappend_r<T>(d1: &D<T>, d2: &D<T>) -> D<T>
+    where T: Clone + 'static
+{
+    if d2.is_empty() {
+        d1.clone()
+    } else {
+        dappend_r(&d1.snoc(d2.head().clone()), &d2.tail())
+    }
+}
+
+fn empty<T>() -> Cat<T>
+    where T: Clone + 'static
+{
+    Shallow(Deque::empty())
+}
+
+// f の最後と r の先頭の 2 要素を新しい中間の両端キューにする
+fn share<T>(f: &D<T>, r: &D<T>) -> (D<T>, D<T>, D<T>)
+    where T: Clone + 'static
+{
+    let m = D::empty().cons(r.head().clone()).cons(f.last().clone());
+    (f.init(), m, r.tail())
+}
+
+impl <T> Cat<T>
+    where T: Clone + 'static
+{
+    fn is_empty(&self) -> bool {
+        match self {
+            &Shallow(ref d) => d.is_empty(),
+            &Deep(_) => false
+        }
+    }
+
+    fn cons(&self, x: Elem<T>) -> Cat<T> {
+        match self {
+            &Shallow(ref d) => Shallow(d.cons(x)),
+            &Deep(ref q) => deep(q.0.cons(x), q.1.clone(), q.2.clone(), q.3.clone(), q.4.clone())
+        }
+    }
+
+    fn head(&self) -> &Elem<T> {
+        match self {
+            &Shallow(ref d) => d.head(),
+            &Deep(ref q) => q.0.head()
+        }
+    }
+
+    fn snoc(&self, x: Elem<T>) -> Cat<T> {
+        match self {
+            &Shallow(ref d) => Shallow(d.snoc(x)),
+            &Deep(ref q) => deep(q.0.clone(), q.1.clone(), q.2.clone(), q.3.clone(), q.4.snoc(x))
+        }
+    }
+
+    fn last(&self) -> &Elem<T> {
+        match self {
+            &Shallow(ref d) => d.last(),
+            &Deep(ref q) => q.4.last()
+        }
+    }
+
+    fn replace_head(&self, x: Elem<T>) -> Cat<T> {
+        match self {
+            &Shallow(ref d) => Shallow(d.tail().cons(x)),
+            &Deep(ref q) => deep(q.0.tail().cons(x), q.1.clone(), q.2.clone(), q.3.clone(), q.4.clone())
+        }
+    }
+
+    fn replace_last(&self, x: Elem<T>) -> Cat<T> {
+        match self {
+            &Shallow(ref d) => Shallow(d.init().snoc(x)),
+            &Deep(ref q) => deep(q.0.clone(), q.1.clone(), q.2.clone(), q.3.clone(), q.4.init().snoc(x))
+        }
+    }
+
+    // 本では a や b に 1 要素足す操作も遅延するが、そうすると連結を繰り返すたびに
+    // サスペンションが前のサスペンションを包み、force するときの再帰が連結の回数だけ深くなる。
+    // 本の解析では a と b の負債は定数個に抑えられるので、その場で force しても償却 O(1) のまま
+    fn append(&self, other: &Cat<T>) -> Cat<T> {
+        match (self, other) {
+            (&Shallow(ref d1), &Shallow(ref d2)) => {
+                if d1.len() < 4 {
+                    Shallow(dappend_l(d1, d2))
+                } else if d2.len() < 4 {
+                    Shallow(dappend_r(d1, d2))
+                } else {
+                    let (f, m, r) = share(d1, d2);
+                    deep(f, Susp::value(empty()), m, Susp::value(empty()), r)
+                }
+            },
+            (&Shallow(ref d), &Deep(ref q)) => {
+                let (ref f, ref a, ref m, ref b, ref r) = **q;
+                if d.len() < 4 {
+                    deep(dappend_l(d, f), a.clone(), m.clone(), b.clone(), r.clone())
+                } else {
+                    let a = Susp::value(a.force().cons(Simple(f.clone())));
+                    deep(d.clone(), a, m.clone(), b.clone(), r.clone())
+                }
+            },
+            (&Deep(ref q), &Shallow(ref d)) => {
+                let (ref f, ref a, ref m, ref b, ref r) = **q;
+                if d.len() < 4 {
+                    deep(f.clone(), a.clone(), m.clone(), b.clone(), dappend_r(r, d))
+                } else {
+                    let b = Susp::value(b.force().snoc(Simple(r.clone())));
+                    deep(f.clone(), a.clone(), m.clone(), b, d.clone())
+                }
+            },
+            (&Deep(ref q1), &Deep(ref q2)) => {
+                let (ref f1, ref a1, ref m1, ref b1, ref r1) = **q1;
+                let (ref f2, ref a2, ref m2, ref b2, ref r2) = **q2;
+                let (r1, m, f2) = share(r1, f2);
+                let a = Susp::value(a1.force().snoc(Compound(Rc::new((m1.clone(), b1.clone(), r1)))));
+                let b = Susp::value(b2.force().cons(Compound(Rc::new((f2, a2.clone(), m2.clone())))));
+                deep(f1.clone(), a, m, b, r2.clone())
+            }
+        }
+    }
+
+    fn tail(&self) -> Cat<T> {
+        match self {
+            &Shallow(ref d) => Shallow(d.tail()),
+            &Deep(ref q) => {
+                let (ref f, ref a, ref m, ref b, ref r) = **q;
+                if f.len() > 3 {
+                    deep(f.tail(), a.clone(), m.clone(), b.clone(), r.clone())
+                } else if !a.force().is_empty() {
+                    match a.force().head() {
+                        &Simple(ref d) => {
+                            let a = a.clone();
+                            deep(dappend_l(&f.tail(), d), Susp::new(move || a.force().tail()), m.clone(), b.clone(), r.clone())
+                        },
+                        &Compound(ref c) => {
+                            let (ref f1, ref a1, ref r1) = **c;
+                            let (a, a1, r1) = (a.clone(), a1.clone(), r1.clone());
+                            let a = Susp::new(move || a1.force().append(&a.force().replace_head(Simple(r1))));
+                            deep(dappend_l(&f.tail(), f1), a, m.clone(), b.clone(), r.clone())
+                        },
+                        &Leaf(_) => panic!("leaf in the middle deque")
+                    }
+                } else if !b.force().is_empty() {
+                    let f = dappend_l(&f.tail(), m);
+                    let b_tail = {
+                        let b = b.clone();
+                        Susp::new(move || b.force().tail())
+                    };
+                    match b.force().head() {
+                        &Simple(ref d) => deep(f, Susp::value(empty()), d.clone(), b_tail, r.clone()),
+                        &Compound(ref c) => {
+                            let (ref f1, ref a1, ref r1) = **c;
+                            let (f1, a1) = (f1.clone(), a1.clone());
+                            let a = Susp::new(move || a1.force().cons(Simple(f1)));
+                            deep(f, a, r1.clone(), b_tail, r.clone())
+                        },
+                        &Leaf(_) => panic!("leaf in the middle deque")
+                    }
+                } else {
+                    Shallow(dappend_l(&f.tail(), m)).append(&Shallow(r.clone()))
+                }
+            }
+        }
+    }
+
+    fn init(&self) -> Cat<T> {
+        match self {
+            &Shallow(ref d) => Shallow(d.init()),
+            &Deep(ref q) => {
+                let (ref f, ref a, ref m, ref b, ref r) = **q;
+                if r.len() > 3 {
+                    deep(f.clone(), a.clone(), m.clone(), b.clone(), r.init())
+                } else if !b.force().is_empty() {
+                    match b.force().last() {
+                        &Simple(ref d) => {
+                            let b = b.clone();
+                            deep(f.clone(), a.clone(), m.clone(), Susp::new(move || b.force().init()), dappend_r(d, &r.init()))
+                        },
+                        &Compound(ref c) => {
+                            let (ref f1, ref a1, ref r1) = **c;
+                            let (b, a1, f1) = (b.clone(), a1.clone(), f1.clone());
+                            let b = Susp::new(move || b.force().replace_last(Simple(f1)).append(a1.force()));
+                            deep(f.clone(), a.clone(), m.clone(), b, dappend_r(r1, &r.init()))
+                        },
+                        &Leaf(_) => panic!("leaf in the middle deque")
+                    }
+                } else if !a.force().is_empty() {
+                    let r = dappend_r(m, &r.init());
+                    let a_init = {
+                        let a = a.clone();
+                        Susp::new(move || a.force().init())
+                    };
+                    match a.force().last() {
+                        &Simple(ref d) => deep(f.clone(), a_init, d.clone(), Susp::value(empty()), r),
+                        &Compound(ref c) => {
+                            let (ref f1, ref a1, ref r1) = **c;
+                            let (a1, r1) = (a1.clone(), r1.clone());
+                            let b = Susp::new(move || a1.force().snoc(Simple(r1)));
+                            deep(f.clone(), a_init, f1.clone(), b, r)
+                        },
+                        &Leaf(_) => panic!("leaf in the middle deque")
+                    }
+                } else {
+                    Shallow(f.clone()).append(&Shallow(dappend_r(m, &r.init())))
+                }
+            }
+        }
+    }
+}
+
+// cons, snoc, head, last, tail, init, append はすべて償却 O(1)
+pub struct ImplicitCatenableDeque<T>(Cat<T>);
+
+impl <T> Clone for ImplicitCatenableDeque<T>
+    where T: Clone
+{
+    fn clone(&self) -> ImplicitCatenableDeque<T> {
+        ImplicitCatenableDeque(self.0.clone())
+    }
+}
+
+fn leaf<T>(x: &Elem<T>) -> &T {
+    match x {
+        &Leaf(ref x) => x,
+        _ => panic!("compound element in the top-level deque")
+    }
+}
+
+impl <T> Deque<T> for ImplicitCatenableDeque<T>
+    where T: Clone + 'static
+{
+    fn empty() -> ImplicitCatenableDeque<T> {
+        ImplicitCatenableDeque(empty())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn cons(&self, x: T) -> ImplicitCatenableDeque<T> {
+        ImplicitCatenableDeque(self.0.cons(Leaf(x)))
+    }
+
+    fn head(&self) -> &T {
+        leaf(self.0.head())
+    }
+
+    fn tail(&self) -> ImplicitCatenableDeque<T> {
+        ImplicitCatenableDeque(self.0.tail())
+    }
+
+    fn snoc(&self, x: T) -> ImplicitCatenableDeque<T> {
+        ImplicitCatenableDeque(self.0.snoc(Leaf(x)))
+    }
+
+    fn last(&self) -> &T {
+        leaf(self.0.last())
+    }
+
+    fn init(&self) -> ImplicitCatenableDeque<T> {
+        ImplicitCatenableDeque(self.0.init())
+    }
+}
+
+impl <T> CatenableDeque<T> for ImplicitCatenableDeque<T>
+    where T: Clone + 'static
+{
+    fn append(&self, other: &ImplicitCatenableDeque<T>) -> ImplicitCatenableDeque<T> {
+        ImplicitCatenableDeque(self.0.append(&other.0))
+    }
+}
+
+impl <T> fmt::Debug for ImplicitCatenableDeque<T>
+    where T: fmt::Debug + Clone + 'static
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut d = self.clone();
+        write!(f, "[")?;
+        while !d.is_empty() {
+            write!(f, "{:?}", d.head())?;
+            d = d.tail();
+            if !d.is_empty() { write!(f, ", ")? }
+        }
+        write!(f, "]")
+    }
+}
@@ -0,0 +1,189 @@
+/// 11.1 暗黙的な再帰的減速によるキュー
+use std::rc::Rc;
+use std::fmt;
+
+use super::{Susp, Queue};
+
+// 中間のキューの要素はペアで、その中間のキューの要素はペアのペアになる (多相再帰)。
+// Rust では型が無限に展開されてしまうので、各段の要素を 1 つの型にまとめて扱う。
+enum Elem<T> {
+    Leaf(T),
+    Pair(Rc<(Elem<T>, Elem<T>)>)
+}
+use self::Elem::*;
+
+impl <T> Clone for Elem<T>
+    where T: Clone
+{
+    fn clone(&self) -> Elem<T> {
+        match self {
+            &Leaf(ref x) => Leaf(x.clone()),
+            &Pair(ref p) => Pair(p.clone())
+        }
+    }
+}
+
+// front は One か Two、rear は Zero か One
+enum Digit<T> {
+    Zero,
+    One(Elem<T>),
+    Two(Elem<T>, Elem<T>)
+}
+use self::Digit::*;
+
+impl <T> Clone for Digit<T>
+    where T: Clone
+{
+    fn clone(&self) -> Digit<T> {
+        match self {
+            &Zero => Zero,
+            &One(ref x) => One(x.clone()),
+            &Two(ref x, ref y) => Two(x.clone(), y.clone())
+        }
+    }
+}
+
+enum Node<T> {
+    Shallow(Digit<T>),
+    Deep(Rc<(Digit<T>, Susp<Node<T>>, Digit<T>)>)
+}
+use self::Node::*;
+
+impl <T> Clone for Node<T>
+    where T: Clone
+{
+    fn clone(&self) -> Node<T> {
+        match self {
+            &Shallow(ref d) => Shallow(d.clone()),
+            &Deep(ref q) => Deep(q.clone())
+        }
+    }
+}
+
+impl <T> Node<T>
+    where T: Clone + 'static
+{
+    fn is_empty(&self) -> bool {
+        match self {
+            &Shallow(Zero) => true,
+            _ => false
+        }
+    }
+
+    fn snoc(&self, y: Elem<T>) -> Node<T> {
+        match self {
+            &Shallow(Zero) => Shallow(One(y)),
+            &Shallow(One(ref x)) => Deep(Rc::new((Two(x.clone(), y), Susp::value(Shallow(Zero)), Zero))),
+            &Shallow(Two(_, _)) => panic!("shallow queue with two elements"),
+            &Deep(ref q) => {
+                let (ref f, ref m, ref r) = **q;
+                match r {
+                    &Zero => Deep(Rc::new((f.clone(), m.clone(), One(y)))),
+                    &One(ref x) => {
+                        // 中間のキューへの snoc まで遅延すると、snoc のたびにサスペンションが入れ子になり、
+                        // 最初の tail や drop の再帰が snoc の回数だけ深くなる。ここでは先に force しておく
+                        let p = Pair(Rc::new((x.clone(), y)));
+                        let m = m.force().snoc(p);
+                        Deep(Rc::new((f.clone(), Susp::value(m), Zero)))
+                    },
+                    &Two(_, _) => panic!("rear digit with two elements")
+                }
+            }
+        }
+    }
+
+    fn head(&self) -> &Elem<T> {
+        match self {
+            &Shallow(Zero) => panic!("empty queue"),
+            &Shallow(One(ref x)) => x,
+            &Shallow(Two(ref x, _)) => x,
+            &Deep(ref q) => {
+                match q.0 {
+                    One(ref x) => x,
+                    Two(ref x, _) => x,
+                    Zero => panic!("front digit with no elements")
+                }
+            }
+        }
+    }
+
+    fn tail(&self) -> Node<T> {
+        match self {
+            &Shallow(Zero) => panic!("empty queue"),
+            &Shallow(One(_)) => Shallow(Zero),
+            &Shallow(Two(_, ref y)) => Shallow(One(y.clone())),
+            &Deep(ref q) => {
+                let (ref f, ref m, ref r) = **q;
+                match f {
+                    &Two(_, ref y) => Deep(Rc::new((One(y.clone()), m.clone(), r.clone()))),
+                    &One(_) => {
+                        let inner = m.force();
+                        if inner.is_empty() {
+                            Shallow(r.clone())
+                        } else {
+                            let front = match inner.head() {
+                                &Pair(ref p) => Two(p.0.clone(), p.1.clone()),
+                                &Leaf(_) => panic!("leaf in the middle queue")
+                            };
+                            let inner = inner.clone();
+                            Deep(Rc::new((front, Susp::new(move || inner.tail()), r.clone())))
+                        }
+                    },
+                    &Zero => panic!("front digit with no elements")
+                }
+            }
+        }
+    }
+}
+
+pub struct ImplicitQueue<T>(Node<T>);
+
+impl <T> Clone for ImplicitQueue<T>
+    where T: Clone
+{
+    fn clone(&self) -> ImplicitQueue<T> {
+        ImplicitQueue(self.0.clone())
+    }
+}
+
+impl <T> Queue<T> for ImplicitQueue<T>
+    where T: Clone + 'static
+{
+    fn empty() -> ImplicitQueue<T> {
+        ImplicitQueue(Shallow(Zero))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn snoc(&self, x: T) -> ImplicitQueue<T> {
+        ImplicitQueue(self.0.snoc(Leaf(x)))
+    }
+
+    fn head(&self) -> &T {
+        match self.0.head() {
+            &Leaf(ref x) => x,
+            &Pair(_) => panic!("pair in the top-level queue")
+        }
+    }
+
+    fn tail(&self) -> ImplicitQueue<T> {
+        ImplicitQueue(self.0.tail())
+    }
+}
+
+impl <T> fmt::Debug for ImplicitQueue<T>
+    where T: fmt::Debug + Clone + 'static
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut q = self.clone();
+        write!(f, "[")?;
+        while !q.is_empty() {
+            write!(f, "{:?}", q.head())?;
+            q = q.tail();
+            if !q.is_empty() { write!(f, ", ")? }
+        }
+        write!(f, "]")
+    }
+}
@@ -0,0 +1,93 @@
+/// 6.4.1 遅延二項ヒープ
+use std::fmt;
+use std::iter::FromIterator;
+
+use super::{Susp, Heap, OrderedHeap, Natural, SortedIter};
+use super::binominalheap::BHeap;
+
+// 木のリスト全体をサスペンションに包む。
+// 古いバージョンに何度 insert しても、繰り上がりの計算は force されたときに
+// 一度だけ行われ、結果はメモ化されて共有される。
+// 各操作は引数の版を先に force し、自分の分の計算だけを遅延させる。
+// サスペンションが前の版のサスペンションを包んで n 段連なると、
+// 最初の force で再帰が深くなりスタックが溢れる
+pub struct LazyBinomialHeap<T>(Susp<BHeap<T>>)
+    where T: Clone;
+
+impl <T> Clone for LazyBinomialHeap<T>
+    where T: Clone
+{
+    fn clone(&self) -> LazyBinomialHeap<T> {
+        LazyBinomialHeap(self.0.clone())
+    }
+}
+
+impl <T> Heap<T> for LazyBinomialHeap<T>
+    where T: Ord + Clone + 'static
+{
+    fn empty() -> LazyBinomialHeap<T> {
+        LazyBinomialHeap(Susp::value(Heap::empty()))
+    }
+
+    fn is_empty(&self) -> bool {
+        Heap::is_empty(self.0.force())
+    }
+
+    fn insert(&self, x: T) -> LazyBinomialHeap<T> {
+        let ts = self.0.force().clone();
+        LazyBinomialHeap(Susp::new(move || ts.insert(x)))
+    }
+
+    fn merge(&self, other: &LazyBinomialHeap<T>) -> LazyBinomialHeap<T> {
+        let (ts1, ts2) = (self.0.force().clone(), other.0.force().clone());
+        LazyBinomialHeap(Susp::new(move || ts1.merge(&ts2)))
+    }
+
+    fn find_min(&self) -> &T {
+        self.0.force().find_min()
+    }
+
+    fn delete_min(&self) -> LazyBinomialHeap<T> {
+        let ts = self.0.force().clone();
+        LazyBinomialHeap(Susp::new(move || ts.delete_min()))
+    }
+
+    // insert は償却 O(1) なので、正格な二項ヒープを作ってから包んでも O(n)
+    fn from_iter<I>(iter: I) -> LazyBinomialHeap<T>
+        where I: IntoIterator<Item = T>
+    {
+        LazyBinomialHeap(Susp::value(<BHeap<T> as Heap<T>>::from_iter(iter)))
+    }
+}
+
+impl <T> OrderedHeap<T> for LazyBinomialHeap<T>
+    where T: Ord + Clone + 'static
+{
+    type Order = Natural;
+}
+
+impl <T> FromIterator<T> for LazyBinomialHeap<T>
+    where T: Ord + Clone + 'static
+{
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> LazyBinomialHeap<T> {
+        <LazyBinomialHeap<T> as Heap<T>>::from_iter(iter)
+    }
+}
+
+impl <T> IntoIterator for LazyBinomialHeap<T>
+    where T: Ord + Clone + 'static
+{
+    type Item = T;
+    type IntoIter = SortedIter<LazyBinomialHeap<T>, T>;
+    fn into_iter(self) -> SortedIter<LazyBinomialHeap<T>, T> {
+        self.into_sorted_iter()
+    }
+}
+
+impl <T> fmt::Debug for LazyBinomialHeap<T>
+    where T: fmt::Debug + Clone
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
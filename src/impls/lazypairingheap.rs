@@ -0,0 +1,169 @@
+/// 6.5 遅延ペアリングヒープ
+use std::rc::Rc;
+use std::mem;
+use std::fmt;
+use std::iter::FromIterator;
+
+use super::{Susp, Heap, OrderedHeap, Natural, SortedIter};
+
+// 子は「奇数個目の子」と「残りの子を遅延 merge したもの」の 2 つ。
+// delete_min の merge は遅延されてメモ化されるので、古いバージョンを
+// 何度使い回しても同じ merge が繰り返されることはない。
+// 4 番目の要素は、m を force したときに連鎖して force される未評価のサスペンションの段数の上限
+pub enum LazyPairingHeap<T> {
+    Empty,
+    Tree(Rc<(T, LazyPairingHeap<T>, Susp<LazyPairingHeap<T>>, usize)>)
+}
+use self::LazyPairingHeap::*;
+
+impl <T> Clone for LazyPairingHeap<T> {
+    fn clone(&self) -> LazyPairingHeap<T> {
+        match self {
+            &Empty => Empty,
+            &Tree(ref t) => Tree(t.clone())
+        }
+    }
+}
+
+// 昇順に insert すると根の m が前の m を包むサスペンションの連鎖になり、
+// 最初の delete_min で連鎖全体が再帰的に force されてスタックを使い果たす。
+// 連鎖がこの段数に達したら、包む前に m を force しておく
+const MAX_CHAIN: usize = 256;
+
+impl <T> LazyPairingHeap<T>
+    where T: Ord + Clone + 'static
+{
+    fn link(&self, a: &LazyPairingHeap<T>) -> LazyPairingHeap<T> {
+        match self {
+            &Empty => panic!("link called for empty heap"),
+            &Tree(ref t) => {
+                let (ref x, ref b, ref m, depth) = **t;
+                match b {
+                    &Empty => Tree(Rc::new((x.clone(), a.clone(), m.clone(), depth))),
+                    &Tree(_) => {
+                        // 先に force しても、その結果はメモ化されるので償却計算量は変わらない
+                        let depth = if m.is_forced() {
+                            0
+                        } else if depth >= MAX_CHAIN {
+                            m.force();
+                            0
+                        } else {
+                            depth
+                        };
+                        let (a, b, m) = (a.clone(), b.clone(), m.clone());
+                        Tree(Rc::new((x.clone(), Empty, Susp::new(move || a.merge(&b).merge(m.force())), depth + 1)))
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl <T> Heap<T> for LazyPairingHeap<T>
+    where T: Ord + Clone + 'static
+{
+    fn empty() -> LazyPairingHeap<T> {
+        Empty
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            &Empty => true,
+            &Tree(_) => false
+        }
+    }
+
+    fn insert(&self, x: T) -> LazyPairingHeap<T> {
+        Tree(Rc::new((x, Empty, Susp::value(Empty), 0))).merge(self)
+    }
+
+    fn merge(&self, other: &LazyPairingHeap<T>) -> LazyPairingHeap<T> {
+        match (self, other) {
+            (h, &Empty) => h.clone(),
+            (&Empty, h) => h.clone(),
+            (&Tree(ref t1), &Tree(ref t2)) => {
+                if t1.0 <= t2.0 {
+                    self.link(other)
+                } else {
+                    other.link(self)
+                }
+            }
+        }
+    }
+
+    fn find_min(&self) -> &T {
+        match self {
+            &Empty => panic!("empty heap"),
+            &Tree(ref t) => &t.0
+        }
+    }
+
+    fn delete_min(&self) -> LazyPairingHeap<T> {
+        match self {
+            &Empty => panic!("empty heap"),
+            &Tree(ref t) => t.1.merge(t.2.force())
+        }
+    }
+}
+
+impl <T> OrderedHeap<T> for LazyPairingHeap<T>
+    where T: Ord + Clone + 'static
+{
+    type Order = Natural;
+}
+
+impl <T> FromIterator<T> for LazyPairingHeap<T>
+    where T: Ord + Clone + 'static
+{
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> LazyPairingHeap<T> {
+        <LazyPairingHeap<T> as Heap<T>>::from_iter(iter)
+    }
+}
+
+impl <T> IntoIterator for LazyPairingHeap<T>
+    where T: Ord + Clone + 'static
+{
+    type Item = T;
+    type IntoIter = SortedIter<LazyPairingHeap<T>, T>;
+    fn into_iter(self) -> SortedIter<LazyPairingHeap<T>, T> {
+        self.into_sorted_iter()
+    }
+}
+
+// 他から共有されていない節点なら、子と評価済みの m の中身を取り外して stack に積む
+fn take_children<T>(h: &mut LazyPairingHeap<T>, stack: &mut Vec<LazyPairingHeap<T>>) {
+    if let &mut Tree(ref mut t) = h {
+        if let Some(node) = Rc::get_mut(t) {
+            if let Tree(_) = node.1 {
+                stack.push(mem::replace(&mut node.1, Empty));
+            }
+            if let Some(m) = node.2.take() {
+                stack.push(m);
+            }
+        }
+    }
+}
+
+// 降順に insert すると、新しい根が古いヒープを奇数個目の子に持つ n 段の木になる。
+// 既定の drop はその深さだけ再帰するので、子を取り外してから落とす。
+// 未評価の m は MAX_CHAIN 段までしか連鎖しないので、そのまま落としてよい
+impl <T> Drop for LazyPairingHeap<T> {
+    fn drop(&mut self) {
+        let mut stack = Vec::new();
+        take_children(self, &mut stack);
+        while let Some(mut h) = stack.pop() {
+            take_children(&mut h, &mut stack);
+        }
+    }
+}
+
+impl <T> fmt::Debug for LazyPairingHeap<T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &Empty => write!(f, "E"),
+            &Tree(ref t) => write!(f, "T({:?}, {:?}, {:?})", t.0, t.1, t.2)
+        }
+    }
+}
@@ -1,22 +1,41 @@
 use std::rc::Rc;
+use std::mem;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::fmt;
 
-use super::Heap;
+use super::{Heap, OrderedHeap, SizedHeap, Compare, Natural, SortedIter, Validate, InvariantError};
 
-pub struct LHeapNode<T> {
+pub struct LHeapNode<T, C> {
   pub rank: i32,
+  pub size: usize,
   pub node: T,
-  pub left: LHeap<T>,
-  pub right: LHeap<T>
+  pub left: LHeapBy<T, C>,
+  pub right: LHeapBy<T, C>,
+  compare: PhantomData<C>
 }
 
-#[derive(Clone)]
-pub enum LHeap<T> {
+// C で順序を与える左偏ヒープ
+pub enum LHeapBy<T, C> {
     Empty,
-    Tree(Rc<LHeapNode<T>>)
+    Tree(Rc<LHeapNode<T, C>>)
 }
-use self::LHeap::*;
 
-impl <T> LHeap<T> {
+// 既定の順序の左偏ヒープ。型引数の既定値は式の型推論に効かないので別名にする
+pub type LHeap<T> = LHeapBy<T, Natural>;
+
+use self::LHeapBy::*;
+
+impl <T, C> Clone for LHeapBy<T, C> {
+    fn clone(&self) -> LHeapBy<T, C> {
+        match *self {
+            Empty => Empty,
+            Tree(ref lhn) => Tree(lhn.clone())
+        }
+    }
+}
+
+impl <T, C> LHeapBy<T, C> {
     pub fn rank(&self) -> i32 {
         match *self {
             Empty => 0,
@@ -24,21 +43,66 @@ impl <T> LHeap<T> {
         }
     }
 
-    pub fn make(node: T, left: LHeap<T>, right: LHeap<T>) -> LHeap<T> {
+    pub fn size(&self) -> usize {
+        match *self {
+            Empty => 0,
+            Tree(ref lhn) => lhn.size
+        }
+    }
+
+    pub fn make(node: T, left: LHeapBy<T, C>, right: LHeapBy<T, C>) -> LHeapBy<T, C> {
         let lr = left.rank();
         let rr = right.rank();
+        let size = left.size() + right.size() + 1;
         if lr >= rr {
-            Tree(Rc::new(LHeapNode {rank: rr + 1, node, left, right}))
+            Tree(Rc::new(LHeapNode {rank: rr + 1, size, node, left, right, compare: PhantomData}))
         } else {
-            Tree(Rc::new(LHeapNode {rank: lr + 1, node, right, left}))
+            Tree(Rc::new(LHeapNode {rank: lr + 1, size, node, left: right, right: left, compare: PhantomData}))
+        }
+    }
+
+    // 与えた階数と要素数をそのまま持つ節点を作る。左右の入れ替えも不変条件の確認もしない
+    pub fn from_parts(rank: i32, size: usize, node: T, left: LHeapBy<T, C>, right: LHeapBy<T, C>) -> LHeapBy<T, C> {
+        Tree(Rc::new(LHeapNode {rank, size, node, left, right, compare: PhantomData}))
+    }
+
+    // 右の子が空の節点。階数は 1
+    fn with_left(node: T, left: LHeapBy<T, C>) -> LHeapBy<T, C> {
+        Tree(Rc::new(LHeapNode {rank: 1, size: left.size() + 1, node, left, right: Empty, compare: PhantomData}))
+    }
+}
+
+// 他から共有されていない節点なら、子を取り外して stack に積む
+fn take_children<T, C>(h: &mut LHeapBy<T, C>, stack: &mut Vec<LHeapBy<T, C>>) {
+    if let Tree(ref mut lhn) = *h {
+        if let Some(node) = Rc::get_mut(lhn) {
+            if let Tree(_) = node.left {
+                stack.push(mem::replace(&mut node.left, Empty));
+            }
+            if let Tree(_) = node.right {
+                stack.push(mem::replace(&mut node.right, Empty));
+            }
         }
     }
 }
 
-impl <T> Heap<T> for LHeap<T>
-    where T: Ord + Clone
+// 降順に insert すると with_left で左の枝が n 段になり、既定の drop は
+// その深さだけ再帰する。子を取り外してから落とせば、それぞれの drop は 1 段で済む
+impl <T, C> Drop for LHeapBy<T, C> {
+    fn drop(&mut self) {
+        let mut stack = Vec::new();
+        take_children(self, &mut stack);
+        while let Some(mut h) = stack.pop() {
+            take_children(&mut h, &mut stack);
+        }
+    }
+}
+
+impl <T, C> Heap<T> for LHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
 {
-    fn empty() -> LHeap<T> {LHeap::Empty}
+    fn empty() -> LHeapBy<T, C> {LHeapBy::Empty}
     fn is_empty(&self) -> bool {
         match *self {
             Empty => true,
@@ -46,22 +110,32 @@ impl <T> Heap<T> for LHeap<T>
         }
     }
 
-    fn merge(&self, other: &LHeap<T>) -> LHeap<T> {
+    fn merge(&self, other: &LHeapBy<T, C>) -> LHeapBy<T, C> {
         match (self, other) {
             (&Empty, h) => {h.clone()},
             (h, &Empty) => {h.clone()},
             (&Tree(ref rh1), &Tree(ref rh2)) => {
-                if rh1.node <= rh2.node {
-                    LHeap::make(rh1.node.clone(), rh1.left.clone(), rh1.right.merge(other))
+                if C::le(&rh1.node, &rh2.node) {
+                    LHeapBy::make(rh1.node.clone(), rh1.left.clone(), rh1.right.merge(other))
                 } else {
-                    LHeap::make(rh2.node.clone(), rh2.left.clone(), self.merge(&rh2.right))
+                    LHeapBy::make(rh2.node.clone(), rh2.left.clone(), self.merge(&rh2.right))
                 }
             }
         }
     }
 
-    fn insert(&self, node: T) -> LHeap<T> {
-        Tree(Rc::new(LHeapNode {rank: 1, node, left: Empty, right: Empty})).merge(self)
+    // 演習 3.2 merge を経由せずに直接挿入する
+    fn insert(&self, node: T) -> LHeapBy<T, C> {
+        match self {
+            &Empty => LHeapBy::with_left(node, Empty),
+            &Tree(ref rh) => {
+                if C::le(&node, &rh.node) {
+                    LHeapBy::with_left(node, self.clone())
+                } else {
+                    LHeapBy::make(rh.node.clone(), rh.left.clone(), rh.right.insert(node))
+                }
+            }
+        }
     }
 
     fn find_min(&self) -> &T {
@@ -71,13 +145,81 @@ impl <T> Heap<T> for LHeap<T>
         }
     }
 
-    fn delete_min(&self) -> LHeap<T> {
+    fn delete_min(&self) -> LHeapBy<T, C> {
         match self {
             &Empty => panic!("empty heap"),
             &Tree(ref rh) => rh.left.merge(&rh.right)
         }
     }
+}
+
+impl <T, C> OrderedHeap<T> for LHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
+{
+    type Order = C;
+}
+
+impl <T, C> SizedHeap<T> for LHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
+{
+    fn len(&self) -> usize {
+        self.size()
+    }
+}
 
+impl <T, C> FromIterator<T> for LHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
+{
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> LHeapBy<T, C> {
+        <LHeapBy<T, C> as Heap<T>>::from_iter(iter)
+    }
+}
+
+impl <T, C> IntoIterator for LHeapBy<T, C>
+    where T: Clone,
+          C: Compare<T>
+{
+    type Item = T;
+    type IntoIter = SortedIter<LHeapBy<T, C>, T>;
+    fn into_iter(self) -> SortedIter<LHeapBy<T, C>, T> {
+        self.into_sorted_iter()
+    }
+}
+
+// 左の経路は長くなりうるので、再帰せずに節点を積んで調べる
+impl <T, C> Validate for LHeapBy<T, C>
+    where T: fmt::Debug,
+          C: Compare<T>
+{
+    fn check_invariants(&self) -> Result<(), InvariantError> {
+        let mut stack = vec![self];
+        while let Some(h) = stack.pop() {
+            if let &Tree(ref rh) = h {
+                for child in [&rh.left, &rh.right].iter() {
+                    if let &&Tree(ref c) = child {
+                        if !C::le(&rh.node, &c.node) {
+                            return Err(InvariantError::HeapOrder(format!("child {:?} comes before parent {:?}.", c.node, rh.node)))
+                        }
+                    }
+                }
+                if rh.left.rank() < rh.right.rank() {
+                    return Err(InvariantError::Rank(format!("On node {:?}, left rank is {} but right is {}.", rh.node, rh.left.rank(), rh.right.rank())))
+                }
+                if rh.rank != rh.right.rank() + 1 {
+                    return Err(InvariantError::Rank(format!("Node {:?} has rank {} but its right spine has length {}.", rh.node, rh.rank, rh.right.rank() + 1)))
+                }
+                if rh.size != rh.left.size() + rh.right.size() + 1 {
+                    return Err(InvariantError::Size(format!("Node {:?} records {} elements but has {}.", rh.node, rh.size, rh.left.size() + rh.right.size() + 1)))
+                }
+                stack.push(&rh.left);
+                stack.push(&rh.right);
+            }
+        }
+        Ok(())
+    }
 }
 
 pub fn run() {
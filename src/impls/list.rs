@@ -1,5 +1,6 @@
 use std::rc::Rc;
 use std::iter::{FromIterator};
+use std::mem;
 
 /// 2.1 リスト
 
@@ -13,6 +14,17 @@ pub trait Stack<T>
     fn head(&self) -> &T; // panic if the stack is empty.
     fn tail(&self) -> &Self; // panic if the stack is empty.
 
+    // 先頭から数えるので O(n)。長さを持っている実装は上書きする
+    fn len(&self) -> usize {
+        let mut n = 0;
+        let mut xs = self;
+        while !xs.is_empty() {
+            n += 1;
+            xs = xs.tail();
+        }
+        n
+    }
+
     fn append(&self, ys: &Self) -> Self
         where Self: Clone
     {
@@ -26,10 +38,11 @@ pub trait Stack<T>
 
 // List の Clone セマンティクス的にはただ内部の参照カウントを増加
 // させるだけで、実際にメモリコピーが行われるわけではない
+// Cons の 3 番目の要素はリストの長さ
 #[derive(Clone)]
 pub enum List<T> {
     Nil,
-    Cons(Rc<(T, List<T>)>)
+    Cons(Rc<(T, List<T>, usize)>)
 }
 use self::List::*;
 
@@ -61,7 +74,7 @@ impl <T> List<T>
     }
 
     pub fn singleton(x: T) -> List<T> {
-        Cons(Rc::new((x, List::empty())))
+        Cons(Rc::new((x, List::empty(), 1)))
     }
 
     pub fn decom(&self) -> (&T, &List<T>) {
@@ -79,10 +92,7 @@ impl <T> List<T>
     }
 
     pub fn count(&self) -> usize {
-        match self {
-            &Nil => 0,
-            &Cons(ref rx) => 1 + rx.1.count()
-        }
+        self.len()
     }
 
     pub fn split_at(&self, n: usize) -> (List<T>, List<T>) {
@@ -102,6 +112,54 @@ impl <T> List<T>
 
 }
 
+impl <T> List<T> {
+    // 他から共有されていない先頭のセルから要素を取り出して out に積み、自分は空になる。
+    // 共有されているセルから先は他の所有者に任せる。要素の Clone は要らない
+    pub fn take_unshared(&mut self, out: &mut Vec<T>) {
+        loop {
+            let rx = match *self {
+                Cons(ref rx) if Rc::strong_count(rx) == 1 => rx.clone(),
+                _ => {
+                    *self = Nil;
+                    return
+                }
+            };
+            *self = Nil;
+            match Rc::try_unwrap(rx) {
+                Ok((x, rest, _)) => {
+                    out.push(x);
+                    *self = rest;
+                },
+                Err(_) => return
+            }
+        }
+    }
+}
+
+// 長いリストを再帰的に解放するとスタックが溢れるので、
+// 自分しか参照していないセルは後続を 1 つずつ切り離しながらループで解放する
+impl <T> Drop for List<T> {
+    fn drop(&mut self) {
+        let mut next = match *self {
+            Cons(ref mut rx) => match Rc::get_mut(rx) {
+                Some(cell) => mem::replace(&mut cell.1, Nil),
+                None => return
+            },
+            Nil => return
+        };
+        loop {
+            let tail = match next {
+                Cons(ref mut rx) => match Rc::get_mut(rx) {
+                    Some(cell) => mem::replace(&mut cell.1, Nil),
+                    None => return
+                },
+                Nil => return
+            };
+            next = tail;
+        }
+    }
+}
+
 pub struct Iter<'a, T: 'a> {
     point: &'a List<T>
 }
@@ -196,7 +254,7 @@ impl <T> Stack<T> for List<T>
         }
     }
     fn snoc(&self, x: T) -> List<T> {
-        Cons(Rc::new((x, self.clone())))
+        Cons(Rc::new((x, self.clone(), self.len() + 1)))
     }
     fn head(&self) -> &T {
         if let &List::Cons(ref xxs) = self {
@@ -212,5 +270,11 @@ impl <T> Stack<T> for List<T>
             panic!("nil tail")
         }
     }
+    fn len(&self) -> usize {
+        match self {
+            &List::Nil => 0,
+            &List::Cons(ref xxs) => xxs.2
+        }
+    }
 }
 
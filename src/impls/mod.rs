@@ -9,21 +9,66 @@ pub use self::set::{Set};
 pub mod binsearchtree;
 
 pub mod leftistheap;
+pub mod weightbiasedheap;
+pub mod skewheap;
+pub mod randomizedheap;
 
 mod heap;
-pub use self::heap::{Heap};
+pub use self::heap::{Heap, OrderedHeap, SizedHeap, SortedIter, heap_sort};
+
+mod compare;
+pub use self::compare::{Compare, Natural, Reversed, KeyFn, ByKey};
+
+mod validate;
+pub use self::validate::{Validate, InvariantError};
 
 pub mod binominalheap;
 pub mod binominalheap2;
+pub mod scheduledbinomialheap;
+pub mod lazybinomialheap;
+pub mod skewbinomialheap;
+pub mod bootstrappedheap;
 
 pub mod explicitminheap;
+pub use self::explicitminheap::ExplicitMinHeap;
+
+pub mod pairingheap;
+pub mod lazypairingheap;
+pub mod splayheap;
 
 pub mod redblacktree;
 
-// use self::stopwatch::Stopwatch;
+mod deque;
+pub use self::deque::{Deque, CatenableDeque};
+
+pub mod batcheddeque;
+
+pub mod stream;
+pub use self::stream::{Susp, Stream};
+
+pub mod bankersdeque;
+pub mod realtimedeque;
+pub mod catenabledeque;
+pub mod implicitcatenabledeque;
+pub mod realtimecatenabledeque;
+
+mod queue;
+pub use self::queue::{Queue};
+
+pub mod batchedqueue;
+pub mod bankersqueue;
+pub mod bootstrappedqueue;
+pub mod implicitqueue;
+
+pub mod persistence;
+
+pub mod random;
+
+use self::stopwatch::Stopwatch;
 
 pub fn run() {
     redblacktree();
+    persistence::run();
 }
 
 use std::cell::RefCell;
@@ -49,6 +94,12 @@ impl TvBuilder {
     fn inc_counter(&self) {
         *self.cmp_counter.borrow_mut() += 1;
     }
+    fn count(&self) -> i32 {
+        *self.cmp_counter.borrow()
+    }
+    fn reset_counter(&self) {
+        *self.cmp_counter.borrow_mut() = 0;
+    }
 }
 
 impl <'a> PartialEq for Tv<'a> {
@@ -116,6 +167,7 @@ fn binominalheap() {
 
 }
 
+#[allow(dead_code)]
 fn redblacktree() {
     #![allow(unused_imports)]
     use self::redblacktree::*;
@@ -183,3 +235,1140 @@ fn redblacktree() {
     // println!("{:?}", t1);
     // println!("{:?}", t2);
 }
+
+// 古いバージョンに対して tail を繰り返したときに、
+// 償却計算量が保たれるかどうかを比べる
+#[allow(dead_code)]
+fn deque() {
+    use self::batcheddeque::BatchedDeque;
+    use self::bankersdeque::BankersDeque;
+
+    fn build<D: Deque<i32>>(n: i32) -> D {
+        (0..n).fold(D::empty(), |d, i| d.snoc(i))
+    }
+
+    // 結果を次の操作に使う (一時的な使い方)
+    fn ephemeral<D: Deque<i32>>(d: &D) -> i64 {
+        let sw = Stopwatch::start_new();
+        let mut d = d.tail();
+        while !d.is_empty() {
+            d = d.tail();
+        }
+        sw.elapsed_ms()
+    }
+
+    // 同じ古いバージョンに対して何度も tail する (永続的な使い方)
+    fn persistent<D: Deque<i32>>(d: &D, n: i32) -> i64 {
+        let sw = Stopwatch::start_new();
+        for _ in 0..n {
+            let _ = d.tail();
+        }
+        sw.elapsed_ms()
+    }
+
+    println!("  n   | batched eph | batched per | bankers eph | bankers per | bankers(c=2) per");
+    for j in 1..11 {
+        let n = j * 1000;
+        let batched = build::<BatchedDeque<i32>>(n);
+        let bankers = build::<BankersDeque<i32>>(n);
+        let bankers2 = (0..n).fold(BankersDeque::with_balance(2), |d, i| d.snoc(i));
+        println!("{:>5} | {:>11} | {:>11} | {:>11} | {:>11} | {:>16}",
+                 n,
+                 ephemeral(&batched), persistent(&batched, n),
+                 ephemeral(&bankers), persistent(&bankers, n),
+                 persistent(&bankers2, n));
+    }
+}
+
+// 長いストリームや、それを持つ両端キューを捨ててもスタックが溢れないことを確かめる
+#[allow(dead_code)]
+fn stream_drop() {
+    use self::bankersdeque::BankersDeque;
+
+    let n = 1000000;
+    let s = (0..n).fold(Stream::empty(), |s, i| Stream::cons(i, s));
+    assert_eq!(s.iter().count(), n as usize);
+    drop(s);
+
+    // 遅延した ++ を最後まで評価したもの
+    let s = (0..n).fold(Stream::empty(), |s, i| Stream::cons(i, s));
+    let t = s.append(&Stream::cons(n, Stream::empty()));
+    drop(s);
+    assert_eq!(t.iter().count(), n as usize + 1);
+    drop(t);
+
+    let d = (0..n).fold(<BankersDeque<i32> as Deque<_>>::empty(), |d, i| d.snoc(i));
+    assert_eq!(*d.head(), 0);
+    drop(d);
+}
+
+// 1 操作あたりに評価されたサスペンションの数が定数で抑えられていることを確かめる
+#[allow(dead_code)]
+fn realtimedeque() {
+    use std::collections::VecDeque;
+    use self::realtimedeque::RealTimeDeque;
+    use self::stream::{force_count, reset_force_count};
+
+    let mut d = <RealTimeDeque<i32> as Deque<_>>::empty();
+    let mut model = VecDeque::new();
+    let mut max_forced = 0;
+    for i in 0..10000 {
+        reset_force_count();
+        // 前から入れて後ろから出す期間と、後ろから入れて前から出す期間を交互に作る
+        match (i / 1000 % 2, i % 3) {
+            (0, 2) => { assert_eq!(d.last(), model.back().unwrap()); d = d.init(); model.pop_back(); },
+            (0, _) => { d = d.cons(i); model.push_front(i); },
+            (_, 2) => { assert_eq!(d.head(), model.front().unwrap()); d = d.tail(); model.pop_front(); },
+            (_, _) => { d = d.snoc(i); model.push_back(i); }
+        }
+        max_forced = ::std::cmp::max(max_forced, force_count());
+    }
+    while !d.is_empty() {
+        reset_force_count();
+        assert_eq!(d.head(), model.front().unwrap());
+        d = d.tail();
+        model.pop_front();
+        max_forced = ::std::cmp::max(max_forced, force_count());
+    }
+    println!("max forced suspensions per operation: {}", max_forced);
+    assert!(max_forced <= 20);
+
+    // 長い両端キューを捨ててもスタックが溢れないこと
+    let d = (0..1000000).fold(<RealTimeDeque<i32> as Deque<_>>::empty(), |d, i| d.snoc(i));
+    assert_eq!(*d.head(), 0);
+    assert_eq!(*d.last(), 999999);
+}
+
+#[allow(dead_code)]
+fn queue() {
+    use self::batchedqueue::BatchedQueue;
+    use self::bankersqueue::BankersQueue;
+    use self::bootstrappedqueue::BootstrappedQueue;
+    use self::implicitqueue::ImplicitQueue;
+
+    // n 個 snoc してから、空になるまで tail する
+    fn snoc_then_tail<Q: Queue<i32>>(n: i32) -> i64 {
+        let sw = Stopwatch::start_new();
+        let mut q = (0..n).fold(Q::empty(), |q, i| q.snoc(i));
+        while !q.is_empty() {
+            q = q.tail();
+        }
+        sw.elapsed_ms()
+    }
+
+    // snoc と tail を交互に行う
+    fn interleaved<Q: Queue<i32>>(n: i32) -> i64 {
+        let sw = Stopwatch::start_new();
+        let mut q = Q::empty().snoc(0);
+        for i in 1..n {
+            q = q.snoc(i).tail();
+        }
+        sw.elapsed_ms()
+    }
+
+    println!("   n    | batched s/t | batched int | bankers s/t | bankers int | bootstrapped s/t | bootstrapped int | implicit s/t | implicit int");
+    for j in 1..11 {
+        let n = j * 10000;
+        println!("{:>7} | {:>11} | {:>11} | {:>11} | {:>11} | {:>16} | {:>16} | {:>12} | {:>12}",
+                 n,
+                 snoc_then_tail::<BatchedQueue<i32>>(n),
+                 interleaved::<BatchedQueue<i32>>(n),
+                 snoc_then_tail::<BankersQueue<i32>>(n),
+                 interleaved::<BankersQueue<i32>>(n),
+                 snoc_then_tail::<BootstrappedQueue<i32>>(n),
+                 interleaved::<BootstrappedQueue<i32>>(n),
+                 snoc_then_tail::<ImplicitQueue<i32>>(n),
+                 interleaved::<ImplicitQueue<i32>>(n));
+    }
+}
+
+// VecDeque をモデルにして、ランダムな操作列の結果を比べる。
+// 古いバージョンに戻って操作を続けることもある
+#[allow(dead_code)]
+fn check_queue<Q: Queue<i32> + Clone>(seed: u64, steps: i32) {
+    use std::collections::VecDeque;
+
+    let mut s = seed;
+    let mut q = Q::empty();
+    let mut model = VecDeque::new();
+    let mut saved = vec![];
+    for i in 0..steps {
+        s = s.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        match (s >> 33) % 8 {
+            0 | 1 | 2 | 3 => {
+                q = q.snoc(i);
+                model.push_back(i);
+            },
+            4 | 5 | 6 => if !model.is_empty() {
+                q = q.tail();
+                model.pop_front();
+            },
+            _ => {
+                saved.push((q.clone(), model.clone()));
+                let (old, old_model) = saved[(s >> 40) as usize % saved.len()].clone();
+                q = old;
+                model = old_model;
+            }
+        }
+        assert_eq!(q.is_empty(), model.is_empty());
+        if let Some(x) = model.front() {
+            assert_eq!(q.head(), x);
+        }
+    }
+    while let Some(x) = model.pop_front() {
+        assert_eq!(q.head(), &x);
+        q = q.tail();
+    }
+    assert!(q.is_empty());
+}
+
+#[allow(dead_code)]
+fn implicitqueue() {
+    use self::bankersqueue::BankersQueue;
+    use self::bootstrappedqueue::BootstrappedQueue;
+    use self::implicitqueue::ImplicitQueue;
+
+    for seed in 0..10 {
+        check_queue::<BankersQueue<i32>>(seed, 10000);
+        check_queue::<BootstrappedQueue<i32>>(seed, 10000);
+        check_queue::<ImplicitQueue<i32>>(seed, 10000);
+    }
+    let q = (0..10).fold(<ImplicitQueue<i32> as Queue<_>>::empty(), |q, i| q.snoc(i));
+    println!("{:?}", q);
+
+    // 長いキューでも最初の tail や drop でスタックが溢れないこと
+    fn snoc_tail_drop<Q: Queue<i32>>(n: i32) {
+        let q = (0..n).fold(Q::empty(), |q, i| q.snoc(i));
+        let q = q.tail();
+        assert_eq!(*q.head(), 1);
+    }
+    snoc_tail_drop::<BankersQueue<i32>>(500000);
+    snoc_tail_drop::<ImplicitQueue<i32>>(1000000);
+}
+
+// Vec をモデルにして、連結を含むランダムな操作列の結果を比べる
+#[allow(dead_code)]
+fn catenabledeque() {
+    use self::catenabledeque::SimpleCatenableDeque;
+    use self::implicitcatenabledeque::ImplicitCatenableDeque;
+    use self::stream::{force_count, reset_force_count};
+
+    fn check<D: CatenableDeque<i32> + Clone>(seed: u64, steps: i32) {
+        let mut s = seed;
+        let mut saved = vec![(D::empty(), vec![])];
+        let mut d = D::empty();
+        let mut model: Vec<i32> = vec![];
+        for i in 0..steps {
+            s = s.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            match (s >> 33) % 10 {
+                0 | 1 => {
+                    d = d.cons(i);
+                    model.insert(0, i);
+                },
+                2 | 3 => {
+                    d = d.snoc(i);
+                    model.push(i);
+                },
+                4 | 5 => if !model.is_empty() {
+                    d = d.tail();
+                    model.remove(0);
+                },
+                6 | 7 => if !model.is_empty() {
+                    d = d.init();
+                    model.pop();
+                },
+                8 => {
+                    let (ref other, ref other_model) = saved[(s >> 40) as usize % saved.len()];
+                    if model.len() + other_model.len() < 2000 {
+                        d = d.append(other);
+                        model.extend(other_model.iter().cloned());
+                    }
+                },
+                _ => saved.push((d.clone(), model.clone()))
+            }
+            assert_eq!(d.is_empty(), model.is_empty());
+            if !model.is_empty() {
+                assert_eq!(d.head(), model.first().unwrap());
+                assert_eq!(d.last(), model.last().unwrap());
+            }
+        }
+        for x in model {
+            assert_eq!(d.head(), &x);
+            d = d.tail();
+        }
+        assert!(d.is_empty());
+    }
+
+    for seed in 0..10 {
+        check::<SimpleCatenableDeque<i32>>(seed, 10000);
+        check::<ImplicitCatenableDeque<i32>>(seed, 10000);
+    }
+    let d = (0..5).fold(<SimpleCatenableDeque<i32> as Deque<_>>::empty(), |d, i| d.snoc(i));
+    println!("{:?}", d.append(&d));
+    let d = (0..5).fold(<ImplicitCatenableDeque<i32> as Deque<_>>::empty(), |d, i| d.snoc(i));
+    println!("{:?}", d.append(&d));
+
+    // 単純な版でも、連結を繰り返したあとの tail や drop でスタックが溢れないこと
+    let piece = (0..5).fold(<SimpleCatenableDeque<i32> as Deque<_>>::empty(), |d, i| d.snoc(i));
+    let d = (0..100000).fold(SimpleCatenableDeque::empty(), |d, _| d.append(&piece));
+    let d = d.tail();
+    assert_eq!((*d.head(), *d.last()), (1, 4));
+    drop(d);
+
+    // 小さな両端キューを n 回連結してから両端で取り出す。
+    // 再帰が深くならず、force されるサスペンションの数が操作数に比例すること
+    let n = 100000;
+    let piece = |i: i32| (0..5).fold(<ImplicitCatenableDeque<i32> as Deque<_>>::empty(), |d, j| d.snoc(5 * i + j));
+    reset_force_count();
+    let mut d = ImplicitCatenableDeque::empty();
+    for i in 0..n {
+        d = d.append(&piece(i));
+    }
+    let mut front = 0;
+    let mut back = 5 * n - 1;
+    while !d.is_empty() {
+        assert_eq!(*d.head(), front);
+        d = d.tail();
+        front += 1;
+        if !d.is_empty() {
+            assert_eq!(*d.last(), back);
+            d = d.init();
+            back -= 1;
+        }
+    }
+    assert_eq!(front, back + 1);
+    let ops = (n * 6 + 5 * n) as usize;
+    println!("implicit: {} ops, {} forces", ops, force_count());
+    assert!(force_count() < 10 * ops);
+
+    // 連結で倍々に大きくしてから、先頭から全部取り出す
+    let mut d = piece(0);
+    for _ in 0..15 {
+        d = d.append(&d);
+    }
+    let mut count = 0;
+    while !d.is_empty() {
+        assert_eq!(*d.head(), count % 5);
+        d = d.tail();
+        count += 1;
+    }
+    assert_eq!(count, 5 << 15);
+}
+
+// Vec をモデルにして、すべての操作の結果と不変条件を確かめる。
+// 1 操作あたりに開け閉めした段の数と評価したサスペンションの数が、
+// 要素数によらない定数で抑えられていること
+#[allow(dead_code)]
+fn realtimecatenabledeque() {
+    use self::realtimecatenabledeque::{RealTimeCatenableDeque, work_count, reset_work_count};
+    use self::stream::{force_count, reset_force_count};
+
+    type D = RealTimeCatenableDeque<i32>;
+    const MAX_WORK: usize = 100;
+    const MAX_FORCED: usize = 100;
+
+    struct Meter {
+        work: usize,
+        forced: usize
+    }
+
+    impl Meter {
+        fn run<F: FnOnce() -> D>(&mut self, f: F) -> D {
+            reset_work_count();
+            reset_force_count();
+            let d = f();
+            self.work = ::std::cmp::max(self.work, work_count());
+            self.forced = ::std::cmp::max(self.forced, force_count());
+            d
+        }
+    }
+
+    fn check(seed: u64, steps: i32, meter: &mut Meter) {
+        let mut s = seed;
+        let mut saved = vec![(D::empty(), vec![])];
+        let mut d = D::empty();
+        let mut model: Vec<i32> = vec![];
+        for i in 0..steps {
+            s = s.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            match (s >> 33) % 10 {
+                0 | 1 => {
+                    d = meter.run(|| d.cons(i));
+                    model.insert(0, i);
+                },
+                2 | 3 => {
+                    d = meter.run(|| d.snoc(i));
+                    model.push(i);
+                },
+                4 | 5 => if !model.is_empty() {
+                    d = meter.run(|| d.tail());
+                    model.remove(0);
+                },
+                6 | 7 => if !model.is_empty() {
+                    d = meter.run(|| d.init());
+                    model.pop();
+                },
+                8 => {
+                    let (ref other, ref other_model) = saved[(s >> 40) as usize % saved.len()];
+                    if model.len() + other_model.len() < 2000 {
+                        d = meter.run(|| d.append(other));
+                        model.extend(other_model.iter().cloned());
+                    }
+                },
+                _ => saved.push((d.clone(), model.clone()))
+            }
+            if let Err(e) = d.check_invariants() {
+                panic!("{} after step {}", e, i);
+            }
+            assert_eq!(d.is_empty(), model.is_empty());
+            if !model.is_empty() {
+                assert_eq!(d.head(), model.first().unwrap());
+                assert_eq!(d.last(), model.last().unwrap());
+            }
+        }
+        for x in model {
+            assert_eq!(d.head(), &x);
+            d = meter.run(|| d.tail());
+        }
+        assert!(d.is_empty());
+    }
+
+    let mut small = Meter {work: 0, forced: 0};
+    for seed in 0..10 {
+        check(seed, 3000, &mut small);
+    }
+    println!("small: max work {}, max forced {}", small.work, small.forced);
+    let d = (0..5).fold(D::empty(), |d, i| d.snoc(i));
+    println!("{:?}", d.append(&d));
+
+    // 10 要素ずつの両端キューを n 回連結したものと、倍々に連結したものから、
+    // 両端で交互に取り出す。要素数が増えても 1 操作あたりの仕事量は同じ上限に収まる
+    let mut large = Meter {work: 0, forced: 0};
+    let piece = |i: i32| (0..10).fold(D::empty(), |d, j| d.snoc(10 * i + j));
+    let n = 20000;
+    let mut d = D::empty();
+    for i in 0..n {
+        let p = piece(i);
+        d = large.run(|| d.append(&p));
+    }
+    assert!(d.check_invariants().is_ok());
+    let mut doubled = piece(0);
+    for _ in 0..14 {
+        doubled = large.run(|| doubled.append(&doubled));
+    }
+    assert!(doubled.check_invariants().is_ok());
+    for &(ref d, len) in [(d, 10 * n), (doubled, 10 << 14)].iter() {
+        let mut d = d.clone();
+        let mut front = 0;
+        let mut back = len - 1;
+        while !d.is_empty() {
+            assert_eq!(*d.head(), if len == 10 * n { front } else { front % 10 });
+            d = large.run(|| d.tail());
+            front += 1;
+            if !d.is_empty() {
+                assert_eq!(*d.last(), if len == 10 * n { back } else { back % 10 });
+                d = large.run(|| d.init());
+                back -= 1;
+            }
+        }
+        assert_eq!(front, back + 1);
+    }
+    println!("large: max work {}, max forced {}", large.work, large.forced);
+    assert!(small.work <= MAX_WORK && large.work <= MAX_WORK);
+    assert!(small.forced <= MAX_FORCED && large.forced <= MAX_FORCED);
+
+    // 長い連結の結果を捨ててもスタックが溢れないこと
+    let piece = (0..5).fold(D::empty(), |d, i| d.snoc(i));
+    let d = (0..400000).fold(D::empty(), |d, _| d.append(&piece));
+    assert_eq!(*d.last(), 4);
+}
+
+// 降順に insert した n 段の木も、昇順に insert した子の多い木も、
+// 捨てるときにスタックが溢れないことを確かめる
+#[allow(dead_code)]
+fn pairingheap() {
+    use self::pairingheap::PairingHeap;
+
+    let n = 1000000;
+    let h = (0..n).rev().fold(PairingHeap::empty(), |h, i| h.insert(i));
+    assert_eq!(*h.find_min(), 0);
+    assert_eq!(*h.delete_min().find_min(), 1);
+    drop(h);
+    let h = (0..n).fold(PairingHeap::empty(), |h, i| h.insert(i));
+    assert_eq!(*h.find_min(), 0);
+    drop(h);
+}
+
+// 古いバージョンを何度使い回しても、同じ結果が返ることを確かめる
+#[allow(dead_code)]
+fn lazypairingheap() {
+    use self::lazypairingheap::LazyPairingHeap;
+
+    let old = (0..1000).fold(<LazyPairingHeap<i32> as Heap<_>>::empty(), |h, i| h.insert(i));
+    for _ in 0..1000 {
+        let h = old.delete_min();
+        assert_eq!(*old.find_min(), 0);
+        assert_eq!(*h.find_min(), 1);
+        assert_eq!(*h.delete_min().find_min(), 2);
+    }
+    let mut h = old.merge(&old.delete_min());
+    let mut xs = vec![];
+    while !h.is_empty() {
+        xs.push(*h.find_min());
+        h = h.delete_min();
+    }
+    let mut expected = (0..1000).chain(1..1000).collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(xs, expected);
+
+    // 昇順に insert すると根の m が n 段の連鎖になる。最初の delete_min でも
+    // スタックを使い果たさず、最後まで取り出せること
+    let n = 200_000;
+    let mut h = (0..n).fold(<LazyPairingHeap<i32> as Heap<_>>::empty(), |h, i| h.insert(i));
+    for i in 0..n {
+        assert_eq!(*h.find_min(), i);
+        h = h.delete_min();
+    }
+    assert!(h.is_empty());
+    let h = (0..n).fold(<LazyPairingHeap<i32> as Heap<_>>::empty(), |h, i| h.insert(i));
+    assert_eq!(*h.delete_min().find_min(), 1);
+    drop(h);
+
+    // 降順に insert すると n 段の木になる。捨ててもスタックが溢れないこと
+    let h = (0..1000000).rev().fold(<LazyPairingHeap<i32> as Heap<_>>::empty(), |h, i| h.insert(i));
+    assert_eq!(*h.find_min(), 0);
+    drop(h);
+}
+
+// partition, smaller, bigger を整列済みの Vec と比べてから、
+// ほぼ整列済みの入力でペアリングヒープによるソートと速さを比べる
+#[allow(dead_code)]
+fn splayheap() {
+    use self::splayheap::{self, SplayHeap};
+    use self::pairingheap::PairingHeap;
+
+    // 10 個ごとに隣同士を入れ替えた、ほぼ整列済みの列
+    let nearly_sorted = |n: i32| (0..n).map(|i| if i % 10 == 0 { i + 1 } else if i % 10 == 1 { i - 1 } else { i }).collect::<Vec<_>>();
+
+    let xs = (0..200).map(|i| (i * 37) % 101).collect::<Vec<_>>();
+    let h = xs.iter().fold(SplayHeap::empty(), |h, &x| h.insert(x));
+    let mut sorted = xs.clone();
+    sorted.sort();
+    assert_eq!(h.to_sorted_vec(), sorted);
+    for pivot in -1..102 {
+        let (small, big) = h.partition(&pivot);
+        let expected_small = sorted.iter().cloned().filter(|&x| x <= pivot).collect::<Vec<_>>();
+        let expected_big = sorted.iter().cloned().filter(|&x| x > pivot).collect::<Vec<_>>();
+        assert_eq!(small.to_sorted_vec(), expected_small);
+        assert_eq!(big.to_sorted_vec(), expected_big);
+        assert_eq!(h.smaller(&pivot).to_sorted_vec(), expected_small);
+        assert_eq!(h.bigger(&pivot).to_sorted_vec(), expected_big);
+    }
+    let mut h = SplayHeap::from_sorted(&sorted);
+    for &x in sorted.iter() {
+        assert_eq!(*h.find_min(), x);
+        h = h.delete_min();
+    }
+    assert!(h.is_empty());
+    assert_eq!(splayheap::sort(&xs), sorted);
+
+    // 整列済みの入力では左の枝が n 段の木になる。find_min も drop も再帰しないこと
+    let xs = (0..1_000_000).collect::<Vec<_>>();
+    assert_eq!(splayheap::sort(&xs), xs);
+    let h = xs.iter().fold(SplayHeap::empty(), |h, &x| h.insert(x));
+    assert_eq!(*h.find_min(), 0);
+    drop(h);
+
+    // 同じく n 段の木から delete_min で全部取り出す、merge する、書き出す
+    let mut h = xs.iter().fold(SplayHeap::empty(), |h, &x| h.insert(x));
+    for &x in xs.iter() {
+        assert_eq!(*h.find_min(), x);
+        h = h.delete_min();
+    }
+    assert!(h.is_empty());
+    let evens = xs.iter().filter(|&&x| x % 2 == 0).fold(SplayHeap::empty(), |h, &x| h.insert(x));
+    let odds = xs.iter().filter(|&&x| x % 2 == 1).fold(SplayHeap::empty(), |h, &x| h.insert(x));
+    assert_eq!(evens.merge(&odds).to_sorted_vec(), xs);
+    assert!(format!("{:?}", evens).starts_with("T(T(T("));
+
+    println!("   n    | splay sort | pairing sort");
+    for j in 1..11 {
+        let n = j * 10000;
+        let xs = nearly_sorted(n);
+        let sw = Stopwatch::start_new();
+        splayheap::sort(&xs);
+        let splay = sw.elapsed_ms();
+        let sw = Stopwatch::start_new();
+        let mut h = xs.iter().fold(PairingHeap::empty(), |h, &x| h.insert(x));
+        while !h.is_empty() {
+            h = h.delete_min();
+        }
+        let pairing = sw.elapsed_ms();
+        println!("{:>7} | {:>10} | {:>12}", n, splay, pairing);
+    }
+}
+
+// 二項ヒープをモデルにして、ランダムな操作列の結果を比べる。
+// 古いバージョン同士を merge することもある
+#[allow(dead_code)]
+fn model_check<H>(seed: u64, steps: i32)
+    where H: Heap<i32> + Clone
+{
+    use self::binominalheap::BHeap;
+
+    let mut s = seed;
+    let mut h = H::empty();
+    let mut model = BHeap::empty();
+    let mut olds = vec![(h.clone(), model.clone())];
+    for _ in 0..steps {
+        s = s.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let r = (s >> 33) as i32;
+        match r % 8 {
+            0 | 1 | 2 | 3 => {
+                h = h.insert(r % 100);
+                model = model.insert(r % 100);
+            },
+            4 | 5 if !model.is_empty() => {
+                h = h.delete_min();
+                model = model.delete_min();
+            },
+            6 => {
+                let (ref oh, ref om) = olds[r as usize % olds.len()];
+                h = h.merge(oh);
+                model = model.merge(om);
+            },
+            7 => {
+                olds.push((h.clone(), model.clone()));
+                let (ref oh, ref om) = olds[r as usize % olds.len()];
+                h = oh.clone();
+                model = om.clone();
+            },
+            _ => {}
+        }
+        assert_eq!(h.is_empty(), model.is_empty());
+        if !model.is_empty() {
+            assert_eq!(h.find_min(), model.find_min());
+        }
+    }
+    while !model.is_empty() {
+        assert_eq!(h.find_min(), model.find_min());
+        h = h.delete_min();
+        model = model.delete_min();
+    }
+    assert!(h.is_empty());
+}
+
+#[allow(dead_code)]
+fn skewbinomialheap() {
+    use self::skewbinomialheap::SkewBinomialHeap;
+
+    for seed in 0..20 {
+        model_check::<SkewBinomialHeap<i32>>(seed, 2000);
+    }
+}
+
+// 基本ヒープの族ごとに、古いバージョンを使い回しても二項ヒープと同じ結果になることを確かめる
+#[allow(dead_code)]
+fn bootstrappedheap() {
+    use self::bootstrappedheap::{BootstrappedHeap, SkewBinomial, Pairing};
+
+    for seed in 0..20 {
+        model_check::<BootstrappedHeap<i32, SkewBinomial>>(seed, 2000);
+        model_check::<BootstrappedHeap<i32, Pairing>>(seed, 2000);
+    }
+
+    // 降順に insert すると n 段の入れ子になる。捨ててもスタックが溢れないこと
+    let n = 1000000;
+    let h = (0..n).rev().fold(<BootstrappedHeap<i32> as Heap<_>>::empty(), |h, i| h.insert(i));
+    assert_eq!(*h.delete_min().find_min(), 1);
+    drop(h);
+    let h = (0..n).rev().fold(<BootstrappedHeap<i32, Pairing> as Heap<_>>::empty(), |h, i| h.insert(i));
+    assert_eq!(*h.delete_min().find_min(), 1);
+    drop(h);
+}
+
+// 古いバージョンを使い回しても二項ヒープと同じ結果になり、
+// insert を重ねた後の最初の find_min でスタックが溢れないことを確かめる
+#[allow(dead_code)]
+fn lazybinomialheap() {
+    use self::lazybinomialheap::LazyBinomialHeap;
+    use self::stream::{force_count, reset_force_count};
+
+    for seed in 0..20 {
+        model_check::<LazyBinomialHeap<i32>>(seed, 2000);
+    }
+
+    let mut h = LazyBinomialHeap::empty();
+    for i in (0..100000).rev() {
+        h = h.insert(i);
+    }
+    assert_eq!(*h.find_min(), 0);
+    let h = h.merge(&LazyBinomialHeap::empty().insert(-1)).delete_min();
+    assert_eq!(*h.find_min(), 0);
+
+    // 同じ古いバージョンに何度 insert しても、古いバージョンのサスペンションは最初の 1 回しか
+    // 評価されず、あとは新しいバージョンごとに 1 つずつ評価される
+    let old = (0..(1 << 14) - 1).fold(LazyBinomialHeap::empty(), |h, i| h.insert(i));
+    reset_force_count();
+    for i in 1..1001 {
+        let h = old.insert(-i);
+        assert_eq!(*h.find_min(), -i);
+    }
+    assert_eq!(force_count(), 1 + 1000);
+    assert_eq!(*old.find_min(), 0);
+    assert_eq!(force_count(), 1 + 1000);
+}
+
+// Heap トレイトを通して、n 個 insert してから空になるまで delete_min する時間を比べる
+#[allow(dead_code)]
+fn heaps() {
+    use self::leftistheap::LHeap;
+    use self::weightbiasedheap::WLHeap;
+    use self::skewheap::SkewHeap;
+
+    fn insert_then_delete<H: Heap<i32>>(xs: &[i32]) -> (i64, i64) {
+        let sw = Stopwatch::start_new();
+        let mut h = xs.iter().fold(H::empty(), |h, &x| h.insert(x));
+        let insert = sw.elapsed_ms();
+        let sw = Stopwatch::start_new();
+        let mut prev = i32::min_value();
+        while !h.is_empty() {
+            assert!(prev <= *h.find_min());
+            prev = *h.find_min();
+            h = h.delete_min();
+        }
+        (insert, sw.elapsed_ms())
+    }
+
+    println!("   n    | leftist ins | leftist del | weight ins | weight del | skew ins | skew del");
+    for j in 1..11 {
+        let n = j * 100000;
+        let mut s = j as u64;
+        let xs = (0..n).map(|_| {
+            s = s.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (s >> 33) as i32
+        }).collect::<Vec<_>>();
+        let leftist = insert_then_delete::<LHeap<i32>>(&xs);
+        let weight = insert_then_delete::<WLHeap<i32>>(&xs);
+        let skew = insert_then_delete::<SkewHeap<i32>>(&xs);
+        println!("{:>7} | {:>11} | {:>11} | {:>10} | {:>10} | {:>8} | {:>8}",
+                 n, leftist.0, leftist.1, weight.0, weight.1, skew.0, skew.1);
+    }
+
+    // 降順に insert すると左の枝が n 段になる。捨ててもスタックが溢れないこと
+    fn descending_drop<H: Heap<i32>>(n: i32) {
+        let h = (0..n).rev().fold(H::empty(), |h, i| h.insert(i));
+        assert_eq!(*h.find_min(), 0);
+        assert_eq!(*h.delete_min().find_min(), 1);
+    }
+    descending_drop::<LHeap<i32>>(1000000);
+    descending_drop::<WLHeap<i32>>(1000000);
+    descending_drop::<SkewHeap<i32>>(1000000);
+    let h = (0..3).rev().fold(<SkewHeap<i32> as Heap<_>>::empty(), |h, i| h.insert(i));
+    assert_eq!(format!("{:?}", h), "T(0, T(1, T(2, E, E), E), E)");
+    let h = (0..1000000).rev().fold(<SkewHeap<i32> as Heap<_>>::empty(), |h, i| h.insert(i));
+    assert!(format!("{:?}", h).starts_with("T(0, T(1, "));
+}
+
+// 1 回の insert で評価されるサスペンションの数が定数で抑えられることを確かめる
+#[allow(dead_code)]
+fn scheduledbinomialheap() {
+    use self::scheduledbinomialheap::ScheduledBinomialHeap;
+
+    let mut h = <ScheduledBinomialHeap<i32> as Heap<_>>::empty();
+    let mut max_forced = 0;
+    for i in 0..100000 {
+        stream::reset_force_count();
+        h = h.insert((i * 7919) % 100003);
+        max_forced = ::std::cmp::max(max_forced, stream::force_count());
+    }
+    println!("max forced suspensions per insert: {}", max_forced);
+    assert!(max_forced <= 4);
+
+    let mut prev = i32::min_value();
+    let mut count = 0;
+    while !h.is_empty() {
+        assert!(prev <= *h.find_min());
+        prev = *h.find_min();
+        h = h.delete_min();
+        count += 1;
+    }
+    assert_eq!(count, 100000);
+}
+
+// 同じシードからは同じ形のヒープができ、取り出し順はシードによらないことを確かめる
+#[allow(dead_code)]
+fn randomizedheap() {
+    use self::randomizedheap::RandomizedMeldableHeap;
+    use self::random::XorShift;
+
+    let mut rng = XorShift::new(42);
+    let xs = (0..10000).map(|_| rng.gen_range(1000) as i32).collect::<Vec<_>>();
+    let build = |seed| xs.iter().fold(RandomizedMeldableHeap::with_seed(seed), |h, &x| h.insert(x));
+    assert_eq!(format!("{:?}", build(1)), format!("{:?}", build(1)));
+
+    let mut sorted = xs.clone();
+    sorted.sort();
+    for seed in 0..5 {
+        let mut h = build(seed);
+        for &x in sorted.iter() {
+            assert_eq!(*h.find_min(), x);
+            h = h.delete_min();
+        }
+        assert!(h.is_empty());
+    }
+
+    // 降順に insert すると n 段の木になる。深い木どうしを merge しても、捨てても
+    // スタックが溢れないこと
+    let n = 1000000;
+    let h = (0..n).rev().fold(RandomizedMeldableHeap::with_seed(1), |h, i| h.insert(2 * i));
+    let h2 = (0..n).rev().fold(RandomizedMeldableHeap::with_seed(2), |h, i| h.insert(2 * i + 1));
+    let h = h.merge(&h2).delete_min();
+    assert_eq!(*h.find_min(), 1);
+    drop(h2);
+    drop(h);
+}
+
+// Reversed で最大ヒープに、ByKey で取り出した優先度の順になることを確かめる
+#[allow(dead_code)]
+fn compare() {
+    use self::leftistheap::LHeapBy;
+    use self::binominalheap;
+    use self::binominalheap2;
+
+    // (名前, 優先度) の優先度の順
+    struct Priority;
+    impl KeyFn<(&'static str, i32)> for Priority {
+        type Key = i32;
+        fn key(x: &(&'static str, i32)) -> i32 {
+            x.1
+        }
+    }
+
+    fn drain<H: Heap<T>, T: Clone>(h: H) -> Vec<T> {
+        let mut h = h;
+        let mut xs = vec![];
+        while let Some((x, rest)) = h.pop() {
+            xs.push(x);
+            h = rest;
+        }
+        xs
+    }
+
+    let xs = (0..100).map(|i| (i * 37) % 101).collect::<Vec<_>>();
+    let mut descending = xs.clone();
+    descending.sort_by(|a, b| b.cmp(a));
+    assert_eq!(drain(xs.iter().cloned().collect::<LHeapBy<i32, Reversed>>()), descending);
+    assert_eq!(drain(xs.iter().cloned().collect::<binominalheap::BHeapBy<i32, Reversed>>()), descending);
+    assert_eq!(drain(xs.iter().cloned().collect::<binominalheap2::BHeapBy<i32, Reversed>>()), descending);
+
+    let tasks = vec![("write", 3), ("review", 1), ("deploy", 5), ("test", 2)];
+    let h = tasks.iter().cloned().collect::<LHeapBy<_, ByKey<Priority>>>();
+    assert_eq!(drain(h).iter().map(|t| t.0).collect::<Vec<_>>(), vec!["review", "test", "write", "deploy"]);
+    let h = tasks.iter().cloned().collect::<binominalheap::BHeapBy<_, Reversed<ByKey<Priority>>>>();
+    assert_eq!(drain(h).iter().map(|t| t.0).collect::<Vec<_>>(), vec!["deploy", "write", "test", "review"]);
+}
+
+// heap_sort の結果を Vec::sort と比べ、iter_sorted が元のヒープを変えないことを確かめる
+#[allow(dead_code)]
+fn sorted_iter() {
+    use self::leftistheap::LHeap;
+    use self::binominalheap::BHeap;
+    use self::pairingheap::PairingHeap;
+    use self::splayheap::SplayHeap;
+    use self::random::XorShift;
+
+    let mut rng = XorShift::new(7);
+    let xs = (0..10000).map(|_| rng.gen_range(5000) as i32).collect::<Vec<_>>();
+    let mut sorted = xs.clone();
+    sorted.sort();
+    assert_eq!(heap_sort::<LHeap<_>, _, _>(xs.clone()), sorted);
+    assert_eq!(heap_sort::<BHeap<_>, _, _>(xs.clone()), sorted);
+    assert_eq!(heap_sort::<PairingHeap<_>, _, _>(xs.clone()), sorted);
+    assert_eq!(heap_sort::<SplayHeap<_>, _, _>(xs.clone()), sorted);
+
+    let h = xs.iter().cloned().collect::<LHeap<_>>();
+    assert_eq!(h.iter_sorted().take(10).collect::<Vec<_>>(), &sorted[..10]);
+    assert_eq!(h.iter_sorted().collect::<Vec<_>>(), sorted);
+    let mut count = 0;
+    for (x, y) in h.into_iter().zip(sorted.iter()) {
+        assert_eq!(x, *y);
+        count += 1;
+    }
+    assert_eq!(count, sorted.len());
+}
+
+// 操作のたびに不変条件を検査し、壊したヒープが検出されることを確かめる
+#[allow(dead_code)]
+fn validate() {
+    use self::leftistheap::{LHeap, LHeapBy};
+    use self::weightbiasedheap::{WLHeap, WLHeapNode};
+    use self::binominalheap;
+    use self::binominalheap2;
+    use self::redblacktree::RedBlackTree;
+    use self::random::XorShift;
+    use ::std::rc::Rc;
+
+    fn steps<H>(seed: u64) -> H
+        where H: Heap<i32> + Validate
+    {
+        let mut rng = XorShift::new(seed);
+        let mut h = H::empty();
+        for _ in 0..5000 {
+            h = if rng.gen_range(3) == 0 && !h.is_empty() {
+                h.delete_min()
+            } else if rng.gen_range(10) == 0 {
+                h.merge(&H::from_iter((0..rng.gen_range(50)).map(|_| rng.gen_range(1000) as i32)))
+            } else {
+                h.insert(rng.gen_range(1000) as i32)
+            };
+            h.check_invariants().unwrap();
+        }
+        h
+    }
+    for seed in 0..5 {
+        steps::<LHeap<i32>>(seed);
+        steps::<LHeapBy<i32, Reversed>>(seed);
+        steps::<binominalheap::BHeap<i32>>(seed);
+        steps::<binominalheap2::BHeap<i32>>(seed);
+        steps::<WLHeap<i32>>(seed);
+        model_check::<LHeap<i32>>(seed, 2000);
+        model_check::<WLHeap<i32>>(seed, 2000);
+    }
+
+    // make は階数の小さい方を右に置く
+    let big = (0..7).collect::<LHeap<i32>>();
+    let h = LHeap::make(-1, LHeap::empty(), big.clone());
+    h.check_invariants().unwrap();
+    let h = LHeap::make(-1, big, LHeap::empty().insert(10));
+    h.check_invariants().unwrap();
+    assert_eq!(h.into_sorted_iter().collect::<Vec<_>>(), vec![-1, 0, 1, 2, 3, 4, 5, 6, 10]);
+
+    // Set::insert の方向別の balance と、insert_naive の両方を調べる
+    let mut rng = XorShift::new(1);
+    let mut t = RedBlackTree::Empty;
+    let mut naive = RedBlackTree::Empty;
+    for _ in 0..5000 {
+        let x = rng.gen_range(10000) as i32;
+        t = t.insert(x);
+        t.check_invariants().unwrap();
+        naive = naive.insert_naive(x);
+        naive.check_invariants().unwrap();
+    }
+    // 昇順や降順に挿入すると、同じ側に赤が続く場合ばかりになる
+    let mut t = RedBlackTree::Empty;
+    let mut u = RedBlackTree::Empty;
+    for i in 0..2000 {
+        t = t.insert(i);
+        t.check_invariants().unwrap();
+        u = u.insert(-i);
+        u.check_invariants().unwrap();
+    }
+
+    // 階数が右の経路と合っていない
+    let leaf = |x| LHeap::from_parts(1, 1, x, LHeap::Empty, LHeap::Empty);
+    let broken: LHeap<i32> = LHeap::from_parts(2, 2, 1, leaf(2), LHeap::Empty);
+    match broken.check_invariants() {
+        Err(InvariantError::Rank(s)) => println!("{}", s),
+        r => panic!("unexpected: {:?}", r)
+    }
+    // 子が親より小さい
+    let broken: LHeap<i32> = LHeap::from_parts(1, 2, 3, leaf(2), LHeap::Empty);
+    match broken.check_invariants() {
+        Err(e @ InvariantError::HeapOrder(_)) => println!("{}", e),
+        r => panic!("unexpected: {:?}", r)
+    }
+    // 右の子の方が要素が多い
+    let leaf = |x| WLHeap::Tree(Rc::new(WLHeapNode {size: 1, node: x, left: WLHeap::Empty, right: WLHeap::Empty}));
+    let broken: WLHeap<i32> = WLHeap::Tree(Rc::new(WLHeapNode {size: 2, node: 1, left: WLHeap::Empty, right: leaf(2)}));
+    match broken.check_invariants() {
+        Err(e @ InvariantError::Weight(_)) => println!("{}", e),
+        r => panic!("unexpected: {:?}", r)
+    }
+    // 要素数が子と合っていない
+    let broken: WLHeap<i32> = WLHeap::Tree(Rc::new(WLHeapNode {size: 3, node: 1, left: leaf(2), right: WLHeap::Empty}));
+    match broken.check_invariants() {
+        Err(e @ InvariantError::Size(_)) => println!("{}", e),
+        r => panic!("unexpected: {:?}", r)
+    }
+}
+
+// len が実際の要素数と一致することを確かめる
+#[allow(dead_code)]
+fn sizes() {
+    use self::leftistheap::LHeap;
+    use self::binominalheap;
+    use self::binominalheap2;
+    use self::weightbiasedheap::WLHeap;
+    use self::pairingheap::PairingHeap;
+    use self::binsearchtree::Tree;
+    use self::redblacktree::RedBlackTree;
+    use self::random::XorShift;
+    use ::std::collections::BTreeSet;
+
+    fn steps<H>(seed: u64)
+        where H: SizedHeap<i32>
+    {
+        let mut rng = XorShift::new(seed);
+        let mut h = H::empty();
+        let mut n = 0;
+        for _ in 0..2000 {
+            if rng.gen_range(3) == 0 && n > 0 {
+                h = h.delete_min();
+                n -= 1;
+            } else if rng.gen_range(10) == 0 {
+                let m = rng.gen_range(50) as usize;
+                h = h.merge(&H::from_iter((0..m).map(|_| rng.gen_range(1000) as i32)));
+                n += m;
+            } else {
+                h = h.insert(rng.gen_range(1000) as i32);
+                n += 1;
+            }
+            assert_eq!(h.len(), n);
+        }
+    }
+    for seed in 0..3 {
+        steps::<LHeap<i32>>(seed);
+        steps::<binominalheap::BHeap<i32>>(seed);
+        steps::<binominalheap2::BHeapBy<i32, Reversed>>(seed);
+        steps::<WLHeap<i32>>(seed);
+        // 要素数を持たないヒープも、包めば ExplicitMinHeap が数える
+        steps::<ExplicitMinHeap<PairingHeap<i32>, i32>>(seed);
+    }
+
+    let xs = (0..10000).collect::<List<i32>>();
+    assert_eq!(xs.len(), 10000);
+    assert_eq!(xs.tail().len(), 9999);
+    assert_eq!(List::<i32>::empty().len(), 0);
+
+    let mut rng = XorShift::new(3);
+    let mut model = BTreeSet::new();
+    let mut t = Tree::empty();
+    let mut rbt = RedBlackTree::Empty;
+    for _ in 0..2000 {
+        let x = rng.gen_range(1000) as i32;
+        model.insert(x);
+        t = t.insert(x);
+        rbt = rbt.insert(x);
+        assert_eq!(t.len(), model.len());
+        assert_eq!(rbt.len(), model.len());
+    }
+    rbt.check_invariants().unwrap();
+    assert_eq!(t.clone().into_iter().collect::<Vec<_>>(), model.iter().cloned().collect::<Vec<_>>());
+    assert_eq!(rbt.clone().into_iter().collect::<Vec<_>>(), model.iter().cloned().collect::<Vec<_>>());
+
+    // 要素数を持たない集合は、既定の len で辿って数える
+    #[derive(Clone)]
+    struct ListSet(List<i32>);
+    impl Set<i32> for ListSet {
+        fn empty() -> ListSet { ListSet(List::empty()) }
+        fn member(&self, x: &i32) -> bool { self.0.iter().any(|y| y == x) }
+        fn insert(&self, x: i32) -> ListSet {
+            if self.member(&x) { self.clone() } else { ListSet(self.0.snoc(x)) }
+        }
+    }
+    impl IntoIterator for ListSet {
+        type Item = i32;
+        type IntoIter = IntoIter<i32>;
+        fn into_iter(self) -> IntoIter<i32> { self.0.into_iter() }
+    }
+    let s = model.iter().fold(ListSet::empty(), |s, &x| s.insert(x).insert(x));
+    assert_eq!(s.len(), model.len());
+}
+
+// 包んだヒープと包まないヒープに同じ操作をして、結果が一致することを確かめる
+#[allow(dead_code)]
+fn explicitminheap() {
+    use self::leftistheap::{LHeap, LHeapBy};
+    use self::binominalheap::{BHeap, BHeapBy};
+    use self::random::XorShift;
+
+    // 最小要素の順序は H から決まるので、包むヒープの順序と食い違うことはない
+    fn steps<H>(seed: u64)
+        where H: SizedHeap<i32> + OrderedHeap<i32> + Clone + Validate
+    {
+        let mut rng = XorShift::new(seed);
+        let mut h = H::empty();
+        let mut e = ExplicitMinHeap::<H, i32>::empty();
+        for _ in 0..5000 {
+            if rng.gen_range(3) == 0 && !h.is_empty() {
+                h = h.delete_min();
+                e = e.delete_min();
+            } else if rng.gen_range(10) == 0 {
+                let xs = (0..rng.gen_range(50)).map(|_| rng.gen_range(1000) as i32).collect::<Vec<_>>();
+                h = h.merge(&H::from_iter(xs.clone()));
+                e = e.merge(&ExplicitMinHeap::new(H::from_iter(xs)));
+            } else {
+                let x = rng.gen_range(1000) as i32;
+                h = h.insert(x);
+                e = e.insert(x);
+            }
+            e.check_invariants().unwrap();
+            assert_eq!(e.try_find_min(), h.try_find_min());
+            assert_eq!(e.len(), h.len());
+        }
+        assert_eq!(e.clone().into_inner().iter_sorted().collect::<Vec<_>>(), h.iter_sorted().collect::<Vec<_>>());
+        assert_eq!(e.into_iter().collect::<Vec<_>>(), h.into_sorted_iter().collect::<Vec<_>>());
+    }
+    for seed in 0..3 {
+        steps::<LHeap<i32>>(seed);
+        steps::<LHeapBy<i32, Reversed>>(seed);
+        steps::<BHeap<i32>>(seed);
+        steps::<BHeapBy<i32, Reversed>>(seed);
+    }
+
+    let e = ExplicitMinHeap::new(vec![3, 1, 2].into_iter().collect::<BHeap<i32>>());
+    println!("{:?}", e);
+    assert_eq!(*e.find_min(), 1);
+    let e = e.delete_min().delete_min().delete_min();
+    assert!(e.is_empty());
+    assert!(e.into_inner().is_empty());
+
+    // new は包むヒープの順序で最小要素を選び、要素数はその len から得る
+    let e = ExplicitMinHeap::new(vec![3, 1, 2].into_iter().collect::<LHeapBy<i32, Reversed>>());
+    assert_eq!(*e.find_min(), 3);
+    assert_eq!(e.len(), 3);
+    assert_eq!(e.into_iter().collect::<Vec<_>>(), vec![3, 2, 1]);
+    assert!(ExplicitMinHeap::new(LHeap::<i32>::empty()).is_empty());
+}
+
+// 順序を省略した古い書き方が、型注釈なしでそのまま通ることを確かめる
+#[allow(dead_code)]
+fn default_order() {
+    use self::leftistheap::LHeap;
+    use self::binominalheap;
+    use self::binominalheap2;
+
+    let h = binominalheap::BHeap::empty().insert(3).insert(1);
+    assert_eq!(*h.find_min(), 1);
+    let h = binominalheap2::BHeap::empty().insert(3).insert(1);
+    assert_eq!(*h.find_min(), 1);
+
+    let mut h = LHeap::empty();
+    for i in (0..100).rev() {
+        h = h.insert(i);
+    }
+    assert_eq!(*h.find_min(), 0);
+    let h = h.merge(&LHeap::empty().insert(-1));
+    assert_eq!(*h.delete_min().find_min(), 0);
+
+    // Heap のメソッドだけを書いた外のヒープも、順序の型を書かずにそのまま通る
+    #[derive(Clone)]
+    struct SortedVec(Vec<i32>);
+    impl Heap<i32> for SortedVec {
+        fn empty() -> SortedVec { SortedVec(vec![]) }
+        fn is_empty(&self) -> bool { self.0.is_empty() }
+        fn insert(&self, x: i32) -> SortedVec {
+            let mut v = self.0.clone();
+            let i = v.iter().position(|&y| x <= y).unwrap_or(v.len());
+            v.insert(i, x);
+            SortedVec(v)
+        }
+        fn merge(&self, other: &SortedVec) -> SortedVec {
+            other.0.iter().fold(self.clone(), |h, &x| h.insert(x))
+        }
+        fn find_min(&self) -> &i32 { &self.0[0] }
+        fn delete_min(&self) -> SortedVec { SortedVec(self.0[1..].to_vec()) }
+    }
+    let h = <SortedVec as Heap<_>>::from_iter(vec![3, 1, 2]);
+    assert_eq!(h.iter_sorted().collect::<Vec<_>>(), vec![1, 2, 3]);
+}
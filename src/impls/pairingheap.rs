@@ -0,0 +1,180 @@
+/// 5.5 ペアリングヒープ
+use std::rc::Rc;
+use std::mem;
+use std::fmt;
+use std::iter::FromIterator;
+
+use super::{Stack, List, Heap, OrderedHeap, Natural, SortedIter};
+
+#[derive(Clone)]
+pub enum PairingHeap<T> {
+    Empty,
+    Tree(Rc<(T, List<PairingHeap<T>>)>)
+}
+use self::PairingHeap::*;
+
+impl <T> PairingHeap<T>
+    where T: Ord + Clone
+{
+    // 左から 2 つずつ merge してから、その結果を右から順に merge する。
+    // 再帰しないので、子のリストが長くてもスタックを使い切らない
+    fn merge_pairs(hs: &List<PairingHeap<T>>) -> PairingHeap<T> {
+        let mut pairs = Vec::new();
+        let mut iter = hs.iter();
+        loop {
+            match (iter.next(), iter.next()) {
+                (Some(h1), Some(h2)) => pairs.push(h1.merge(h2)),
+                (Some(h), None) => {
+                    pairs.push(h.clone());
+                    break
+                },
+                _ => break
+            }
+        }
+        pairs.into_iter().rev().fold(Empty, |acc, h| h.merge(&acc))
+    }
+}
+
+impl <T> Heap<T> for PairingHeap<T>
+    where T: Ord + Clone
+{
+    fn empty() -> PairingHeap<T> {
+        Empty
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            &Empty => true,
+            &Tree(_) => false
+        }
+    }
+
+    fn insert(&self, x: T) -> PairingHeap<T> {
+        Tree(Rc::new((x, List::Nil))).merge(self)
+    }
+
+    fn merge(&self, other: &PairingHeap<T>) -> PairingHeap<T> {
+        match (self, other) {
+            (h, &Empty) => h.clone(),
+            (&Empty, h) => h.clone(),
+            (&Tree(ref t1), &Tree(ref t2)) => {
+                let (ref x, ref hs1) = **t1;
+                let (ref y, ref hs2) = **t2;
+                if x <= y {
+                    Tree(Rc::new((x.clone(), hs1.snoc(other.clone()))))
+                } else {
+                    Tree(Rc::new((y.clone(), hs2.snoc(self.clone()))))
+                }
+            }
+        }
+    }
+
+    fn find_min(&self) -> &T {
+        match self {
+            &Empty => panic!("empty heap"),
+            &Tree(ref t) => &t.0
+        }
+    }
+
+    fn delete_min(&self) -> PairingHeap<T> {
+        match self {
+            &Empty => panic!("empty heap"),
+            &Tree(ref t) => PairingHeap::merge_pairs(&t.1)
+        }
+    }
+
+    // insert が O(1) なので、順に insert するだけで O(n) になる
+    fn from_iter<I>(iter: I) -> PairingHeap<T>
+        where I: IntoIterator<Item = T>
+    {
+        iter.into_iter().fold(Heap::empty(), |h: PairingHeap<T>, x| h.insert(x))
+    }
+}
+
+impl <T> OrderedHeap<T> for PairingHeap<T>
+    where T: Ord + Clone
+{
+    type Order = Natural;
+}
+
+impl <T> PairingHeap<T> {
+    // 他から共有されていない節点から要素を取り出して out に積み、自分は空になる。
+    // 順序を使わないので、BootstrappedHeap の drop から呼べる
+    pub fn take_unshared(&mut self, out: &mut Vec<T>) {
+        let mut stack = vec![mem::replace(self, Empty)];
+        while let Some(h) = stack.pop() {
+            let t = match h {
+                Tree(ref t) if Rc::strong_count(t) == 1 => t.clone(),
+                _ => continue
+            };
+            drop(h);
+            if let Ok((x, mut hs)) = Rc::try_unwrap(t) {
+                out.push(x);
+                hs.take_unshared(&mut stack);
+            }
+        }
+    }
+}
+
+// 他から共有されていない節点なら、子のリストから子を取り外して stack に積む
+fn take_children<T>(h: &mut PairingHeap<T>, stack: &mut Vec<PairingHeap<T>>) {
+    if let &mut Tree(ref mut t) = h {
+        if let Some(node) = Rc::get_mut(t) {
+            let mut hs = mem::replace(&mut node.1, List::Nil);
+            loop {
+                let rest = match hs {
+                    List::Cons(ref mut c) => match Rc::get_mut(c) {
+                        Some(cell) => {
+                            stack.push(mem::replace(&mut cell.0, Empty));
+                            mem::replace(&mut cell.1, List::Nil)
+                        },
+                        None => break
+                    },
+                    List::Nil => break
+                };
+                hs = rest;
+            }
+        }
+    }
+}
+
+// 降順に insert すると、新しい根が古いヒープを唯一の子に持つ n 段の木になる。
+// 既定の drop はその深さだけ再帰するので、子を取り外してから落とす
+impl <T> Drop for PairingHeap<T> {
+    fn drop(&mut self) {
+        let mut stack = Vec::new();
+        take_children(self, &mut stack);
+        while let Some(mut h) = stack.pop() {
+            take_children(&mut h, &mut stack);
+        }
+    }
+}
+
+impl <T> FromIterator<T> for PairingHeap<T>
+    where T: Ord + Clone
+{
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> PairingHeap<T> {
+        <PairingHeap<T> as Heap<T>>::from_iter(iter)
+    }
+}
+
+impl <T> IntoIterator for PairingHeap<T>
+    where T: Ord + Clone
+{
+    type Item = T;
+    type IntoIter = SortedIter<PairingHeap<T>, T>;
+    fn into_iter(self) -> SortedIter<PairingHeap<T>, T> {
+        self.into_sorted_iter()
+    }
+}
+
+impl <T> fmt::Debug for PairingHeap<T>
+    where T: fmt::Debug + Clone
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &Empty => write!(f, "E"),
+            &Tree(ref t) => write!(f, "T({:?}, {:?})", t.0, t.1)
+        }
+    }
+}
@@ -0,0 +1,169 @@
+/// 永続性と償却計算量
+// 償却計算量は、古いバージョンを何度も使い回すと成り立たなくなることがある。
+// 保存しておいた古いバージョンに最悪ケースの操作を繰り返し適用して、
+// 比較回数と経過時間を記録する。
+use std::fmt;
+
+use super::stopwatch::Stopwatch;
+use super::{Stack, Heap, Queue, Tv, TvBuilder};
+
+pub struct Report {
+    pub repeats: usize,
+    pub comparisons: i32,
+    pub elapsed_ms: i64
+}
+
+impl fmt::Debug for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ops, {} cmp, {} ms", self.repeats, self.comparisons, self.elapsed_ms)
+    }
+}
+
+// old に op を repeats 回適用する。結果は毎回捨てるので、常に同じ古いバージョンが使われる。
+// 比較回数は old の要素を作った tb で数える
+fn stress<S, F>(tb: &TvBuilder, old: &S, repeats: usize, op: F) -> Report
+    where F: Fn(&S) -> S
+{
+    tb.reset_counter();
+    let sw = Stopwatch::start_new();
+    for _ in 0..repeats {
+        let _ = op(old);
+    }
+    Report {repeats, comparisons: tb.count(), elapsed_ms: sw.elapsed_ms()}
+}
+
+// 要素数 n のスタックの末尾に 1 要素を append する
+fn stack<'a, S>(tb: &'a TvBuilder, n: i32, repeats: usize) -> Report
+    where S: Stack<Tv<'a>> + Clone
+{
+    let old = (0..n).fold(S::empty(), |s, i| s.snoc(tb.v(i)));
+    let one = S::empty().snoc(tb.v(n));
+    stress(tb, &old, repeats, |s| s.append(&one))
+}
+
+// 要素数 n のヒープに insert する。n = 2^k - 1 のとき二項ヒープは全ての階数で繰り上がる
+fn heap<'a, H>(tb: &'a TvBuilder, n: i32, repeats: usize) -> Report
+    where H: Heap<Tv<'a>>
+{
+    let old = (0..n).fold(H::empty(), |h, i| h.insert(tb.v(i)));
+    stress(tb, &old, repeats, |h| h.insert(tb.v(n)))
+}
+
+// heap と同じだが、結果の find_min まで求める。遅延ヒープのサスペンションもここで force される
+fn heap_forced<'a, H>(tb: &'a TvBuilder, n: i32, repeats: usize) -> Report
+    where H: Heap<Tv<'a>>
+{
+    let old = (0..n).fold(H::empty(), |h, i| h.insert(tb.v(i)));
+    stress(tb, &old, repeats, |h| {
+        let h = h.insert(tb.v(n));
+        h.find_min();
+        h
+    })
+}
+
+// 0 から n - 1 を昇順に insert したヒープから delete_min する。
+// ペアリングヒープでは根が n - 1 個の子を持つので、毎回すべての子を merge し直す
+fn heap_delete_min<'a, H>(tb: &'a TvBuilder, n: i32, repeats: usize) -> Report
+    where H: Heap<Tv<'a>>
+{
+    let old = (0..n).fold(H::empty(), |h, i| h.insert(tb.v(i)));
+    stress(tb, &old, repeats, |h| h.delete_min())
+}
+
+// 要素数 n の 2 つのヒープを merge する
+fn heap_merge<'a, H>(tb: &'a TvBuilder, n: i32, repeats: usize) -> Report
+    where H: Heap<Tv<'a>>
+{
+    let old = (0..n).fold(H::empty(), |h, i| h.insert(tb.v(2 * i)));
+    let other = (0..n).fold(H::empty(), |h, i| h.insert(tb.v(2 * i + 1)));
+    stress(tb, &old, repeats, |h| h.merge(&other))
+}
+
+// n 個 snoc したキューを tail する。バッチキューでは毎回 rear 全体の反転が起きる
+fn queue<'a, Q>(tb: &'a TvBuilder, n: i32, repeats: usize) -> Report
+    where Q: Queue<Tv<'a>>
+{
+    let old = (0..n).fold(Q::empty(), |q, i| q.snoc(tb.v(i)));
+    stress(tb, &old, repeats, |q| q.tail())
+}
+
+pub fn run() {
+    use super::List;
+    use super::batchedqueue::BatchedQueue;
+    use super::bankersqueue::BankersQueue;
+    use super::leftistheap::LHeap;
+    use super::pairingheap::PairingHeap;
+    use super::lazypairingheap::LazyPairingHeap;
+    use super::binominalheap;
+    use super::binominalheap2;
+    use super::lazybinomialheap::LazyBinomialHeap;
+    use super::skewbinomialheap::SkewBinomialHeap;
+    use super::bootstrappedheap::BootstrappedHeap;
+
+    // 遅延評価する実装は要素に 'static を求めるので、比較を数える TvBuilder はリークさせておく
+    let tb: &'static TvBuilder = Box::leak(Box::new(TvBuilder::new()));
+    let repeats = 1000;
+
+    println!("queue: tail on an old version, {} times", repeats);
+    println!("   n   | batched ms | bankers ms");
+    for j in 1..11 {
+        let n = j * 1000;
+        let batched = queue::<BatchedQueue<Tv>>(tb, n, repeats);
+        let bankers = queue::<BankersQueue<Tv>>(tb, n, repeats);
+        println!("{:>6} | {:>10} | {:>10}", n, batched.elapsed_ms, bankers.elapsed_ms);
+    }
+
+    // 遅延二項ヒープは結果を force しないと比較が起きないので、find_min まで求めて数える。
+    // 古いバージョンは最初の 1 回で force されてメモ化されるが、新しいバージョンは毎回別の
+    // サスペンションなので、繰り上がりは正格な二項ヒープと同じく毎回計算される
+    println!("heap: insert on an old version of size 2^k - 1, {} times", repeats);
+    println!("   n   | leftist cmp | leftist ms | binominal cmp | binominal ms | binominal2 cmp | binominal2 ms | lazy binominal cmp | lazy binominal ms | pairing cmp | pairing ms | skew cmp | skew ms");
+    for k in 1..15 {
+        let n = (1 << k) - 1;
+        let leftist = heap::<LHeap<Tv>>(tb, n, repeats);
+        let binominal = heap::<binominalheap::BHeap<Tv>>(tb, n, repeats);
+        let binominal2 = heap::<binominalheap2::BHeap<Tv>>(tb, n, repeats);
+        let lazy = heap_forced::<LazyBinomialHeap<Tv>>(tb, n, repeats);
+        let pairing = heap::<PairingHeap<Tv>>(tb, n, repeats);
+        let skew = heap::<SkewBinomialHeap<Tv>>(tb, n, repeats);
+        println!("{:>6} | {:>11} | {:>10} | {:>13} | {:>12} | {:>14} | {:>13} | {:>18} | {:>17} | {:>11} | {:>10} | {:>8} | {:>7}",
+                 n,
+                 leftist.comparisons, leftist.elapsed_ms,
+                 binominal.comparisons, binominal.elapsed_ms,
+                 binominal2.comparisons, binominal2.elapsed_ms,
+                 lazy.comparisons, lazy.elapsed_ms,
+                 pairing.comparisons, pairing.elapsed_ms,
+                 skew.comparisons, skew.elapsed_ms);
+    }
+
+    println!("heap: delete_min on an old version, {} times", repeats);
+    println!("   n   | pairing cmp | pairing ms | lazy pairing cmp | lazy pairing ms");
+    for j in 1..11 {
+        let n = j * 1000;
+        let pairing = heap_delete_min::<PairingHeap<Tv>>(tb, n, repeats);
+        let lazy = heap_delete_min::<LazyPairingHeap<Tv>>(tb, n, repeats);
+        println!("{:>6} | {:>11} | {:>10} | {:>16} | {:>15}",
+                 n, pairing.comparisons, pairing.elapsed_ms, lazy.comparisons, lazy.elapsed_ms);
+    }
+
+    println!("heap: merge two old versions of size n, {} times", repeats);
+    println!("   n   | binominal cmp | binominal ms | skew cmp | skew ms | bootstrapped cmp | bootstrapped ms");
+    for k in 1..15 {
+        let n = (1 << k) - 1;
+        let binominal = heap_merge::<binominalheap::BHeap<Tv>>(tb, n, repeats);
+        let skew = heap_merge::<SkewBinomialHeap<Tv>>(tb, n, repeats);
+        let bootstrapped = heap_merge::<BootstrappedHeap<Tv>>(tb, n, repeats);
+        println!("{:>6} | {:>13} | {:>12} | {:>8} | {:>7} | {:>16} | {:>15}",
+                 n,
+                 binominal.comparisons, binominal.elapsed_ms,
+                 skew.comparisons, skew.elapsed_ms,
+                 bootstrapped.comparisons, bootstrapped.elapsed_ms);
+    }
+
+    println!("stack: append one element to an old version, {} times", repeats);
+    println!("   n   | list ms");
+    for j in 1..6 {
+        let n = j * 1000;
+        println!("{:>6} | {:>7}", n, stack::<List<Tv>>(tb, n, repeats).elapsed_ms);
+    }
+}
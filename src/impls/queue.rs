@@ -0,0 +1,10 @@
+pub trait Queue<T>
+    where T: Clone
+{
+    fn empty() -> Self;
+    fn is_empty(&self) -> bool;
+
+    fn snoc(&self, x: T) -> Self;
+    fn head(&self) -> &T; // panic if the queue is empty.
+    fn tail(&self) -> Self; // panic if the queue is empty.
+}
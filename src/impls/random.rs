@@ -0,0 +1,45 @@
+/// 疑似乱数
+// 外部クレートを使わない、シード付きの小さな疑似乱数生成器 (xorshift64*)。
+// 同じシードからは常に同じ列が得られる
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XorShift {
+    state: u64
+}
+
+pub const DEFAULT_SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+impl XorShift {
+    // 状態が 0 だと 0 しか出てこないので、シードを splitmix64 で混ぜてから使う
+    pub fn new(seed: u64) -> XorShift {
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        XorShift {state: if z == 0 { DEFAULT_SEED } else { z }}
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    pub fn next_bool(&mut self) -> bool {
+        self.next_u64() >> 63 == 1
+    }
+
+    // 0 以上 n 未満
+    pub fn gen_range(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "empty range");
+        self.next_u64() % n
+    }
+}
+
+impl Default for XorShift {
+    fn default() -> XorShift {
+        XorShift::new(DEFAULT_SEED)
+    }
+}
@@ -0,0 +1,181 @@
+/// 乱択融合可能ヒープ
+use std::rc::Rc;
+use std::mem;
+use std::fmt;
+use std::iter::FromIterator;
+
+use super::{Heap, OrderedHeap, Natural, SortedIter};
+use super::random::XorShift;
+
+// 平衡のための情報を持たず、merge のたびにどちらの子へ進むかを乱数で決める。
+// どの操作も期待 O(log n)
+#[derive(Clone)]
+enum Node<T> {
+    Empty,
+    Tree(Rc<(T, Node<T>, Node<T>)>)
+}
+use self::Node::*;
+
+// 乱数の状態もヒープと一緒に持ち歩く。操作のたびに進めた状態を結果に入れるので、
+// 同じシードから同じ操作列を行えば同じ形のヒープになる
+#[derive(Clone)]
+pub struct RandomizedMeldableHeap<T> {
+    rng: XorShift,
+    root: Node<T>
+}
+
+impl <T> RandomizedMeldableHeap<T> {
+    pub fn with_seed(seed: u64) -> RandomizedMeldableHeap<T> {
+        RandomizedMeldableHeap {rng: XorShift::new(seed), root: Empty}
+    }
+}
+
+// 他から共有されていない節点なら、子を取り外して stack に積む
+fn take_children<T>(h: &mut Node<T>, stack: &mut Vec<Node<T>>) {
+    if let &mut Tree(ref mut t) = h {
+        if let Some(node) = Rc::get_mut(t) {
+            if let Tree(_) = node.1 {
+                stack.push(mem::replace(&mut node.1, Empty));
+            }
+            if let Tree(_) = node.2 {
+                stack.push(mem::replace(&mut node.2, Empty));
+            }
+        }
+    }
+}
+
+// 降順に insert すると、どの節点も子を 1 つだけ持つ n 段の木になる。
+// 既定の drop はその深さだけ再帰するので、子を取り外してから落とす
+impl <T> Drop for Node<T> {
+    fn drop(&mut self) {
+        let mut stack = Vec::new();
+        take_children(self, &mut stack);
+        while let Some(mut h) = stack.pop() {
+            take_children(&mut h, &mut stack);
+        }
+    }
+}
+
+// 辿る経路は最悪で木の深さになるので、再帰せずに経路を積んでから組み立てる。
+// 乱数は上から順に引くので、再帰で書いたときと同じ形になる
+fn meld<T>(rng: &mut XorShift, h1: &Node<T>, h2: &Node<T>) -> Node<T>
+    where T: Ord + Clone
+{
+    // (根の要素, 左に進んだか, 進まなかった側の子)
+    let mut path = Vec::new();
+    let (mut h1, mut h2) = (h1, h2);
+    let rest = loop {
+        match (h1, h2) {
+            (h, &Empty) => break h.clone(),
+            (&Empty, h) => break h.clone(),
+            (&Tree(ref t1), &Tree(ref t2)) => {
+                let (t, other) = if t1.0 <= t2.0 { (t1, h2) } else { (t2, h1) };
+                let (ref x, ref a, ref b) = **t;
+                if rng.next_bool() {
+                    path.push((x, true, b));
+                    h1 = a;
+                } else {
+                    path.push((x, false, a));
+                    h1 = b;
+                }
+                h2 = other;
+            }
+        }
+    };
+    path.into_iter().rev().fold(rest, |h, (x, left, c)| {
+        if left {
+            Tree(Rc::new((x.clone(), h, c.clone())))
+        } else {
+            Tree(Rc::new((x.clone(), c.clone(), h)))
+        }
+    })
+}
+
+impl <T> Heap<T> for RandomizedMeldableHeap<T>
+    where T: Ord + Clone
+{
+    fn empty() -> RandomizedMeldableHeap<T> {
+        RandomizedMeldableHeap {rng: XorShift::default(), root: Empty}
+    }
+
+    fn is_empty(&self) -> bool {
+        match self.root {
+            Empty => true,
+            Tree(_) => false
+        }
+    }
+
+    fn insert(&self, x: T) -> RandomizedMeldableHeap<T> {
+        let mut rng = self.rng;
+        let root = meld(&mut rng, &Tree(Rc::new((x, Empty, Empty))), &self.root);
+        RandomizedMeldableHeap {rng, root}
+    }
+
+    // 乱数の状態は self のものを引き継ぐ
+    fn merge(&self, other: &RandomizedMeldableHeap<T>) -> RandomizedMeldableHeap<T> {
+        let mut rng = self.rng;
+        let root = meld(&mut rng, &self.root, &other.root);
+        RandomizedMeldableHeap {rng, root}
+    }
+
+    fn find_min(&self) -> &T {
+        match self.root {
+            Empty => panic!("empty heap"),
+            Tree(ref t) => &t.0
+        }
+    }
+
+    fn delete_min(&self) -> RandomizedMeldableHeap<T> {
+        match self.root {
+            Empty => panic!("empty heap"),
+            Tree(ref t) => {
+                let mut rng = self.rng;
+                let root = meld(&mut rng, &t.1, &t.2);
+                RandomizedMeldableHeap {rng, root}
+            }
+        }
+    }
+}
+
+impl <T> OrderedHeap<T> for RandomizedMeldableHeap<T>
+    where T: Ord + Clone
+{
+    type Order = Natural;
+}
+
+impl <T> FromIterator<T> for RandomizedMeldableHeap<T>
+    where T: Ord + Clone
+{
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> RandomizedMeldableHeap<T> {
+        <RandomizedMeldableHeap<T> as Heap<T>>::from_iter(iter)
+    }
+}
+
+impl <T> IntoIterator for RandomizedMeldableHeap<T>
+    where T: Ord + Clone
+{
+    type Item = T;
+    type IntoIter = SortedIter<RandomizedMeldableHeap<T>, T>;
+    fn into_iter(self) -> SortedIter<RandomizedMeldableHeap<T>, T> {
+        self.into_sorted_iter()
+    }
+}
+
+impl <T> fmt::Debug for Node<T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &Empty => write!(f, "E"),
+            &Tree(ref t) => write!(f, "T({:?}, {:?}, {:?})", t.0, t.1, t.2)
+        }
+    }
+}
+
+impl <T> fmt::Debug for RandomizedMeldableHeap<T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.root)
+    }
+}
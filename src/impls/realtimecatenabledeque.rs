@@ -0,0 +1,855 @@
+/// 11 章の注 リアルタイムの連結可能両端キュー (Kaplan と Tarjan)
+use std::rc::Rc;
+use std::cell::Cell;
+use std::cmp;
+use std::fmt;
+
+use super::{Deque, CatenableDeque, Validate, InvariantError};
+use super::realtimedeque::RealTimeDeque;
+
+// split と join で段を開け閉めした回数と、バッファ間で移した要素の数。
+// 1 操作あたりの仕事量が定数で抑えられていることの確認に使う
+thread_local!(static WORK_COUNT: Cell<usize> = Cell::new(0));
+
+pub fn work_count() -> usize {
+    WORK_COUNT.with(|c| c.get())
+}
+
+pub fn reset_work_count() {
+    WORK_COUNT.with(|c| c.set(0))
+}
+
+fn tick() {
+    WORK_COUNT.with(|c| c.set(c.get() + 1))
+}
+
+// 最上段の要素は Leaf、下の段の要素は格納された三つ組 Small か Big になる。
+// Small のバッファと Big の両端のバッファは 3 要素以上。Big の子は空でもよい
+enum Elem<T> {
+    Leaf(T),
+    Small(Rc<Buf<T>>),
+    Big(Rc<(Buf<T>, Chain<T>, Buf<T>)>)
+}
+use self::Elem::*;
+
+impl <T> Clone for Elem<T>
+    where T: Clone
+{
+    fn clone(&self) -> Elem<T> {
+        match self {
+            &Leaf(ref x) => Leaf(x.clone()),
+            &Small(ref b) => Small(b.clone()),
+            &Big(ref t) => Big(t.clone())
+        }
+    }
+}
+
+// バッファは最悪 O(1) の両端キュー
+type Buf<T> = RealTimeDeque<Elem<T>>;
+
+// 子を持つ三つ組の色。注目するバッファの要素数が 8 以上なら Green、
+// 7 なら Yellow、6 なら Orange、5 なら Red。子を持たない三つ組は常に Green
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Color {
+    Red,
+    Orange,
+    Yellow,
+    Green
+}
+use self::Color::*;
+
+// Only は両方のバッファに、Left は prefix だけに、Right は suffix だけに注目する。
+// 子を持つ Only は両端 5 要素以上、子を持たない Only は合わせて 1 要素以上。
+// Left は prefix が 5 要素以上で suffix がちょうど 2 要素、Right はその逆
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Kind {
+    Only,
+    Left,
+    Right
+}
+
+// 子を除いた三つ組。color は記録した色で、要素数から決まる実際の色より良くはならない。
+// push や inject で要素数が増えても色は書き換えないので、優先パスの付け替えが起きない
+struct Node<T> {
+    kind: Kind,
+    color: Color,
+    prefix: Buf<T>,
+    suffix: Buf<T>
+}
+
+impl <T> Clone for Node<T> {
+    fn clone(&self) -> Node<T> {
+        Node {kind: self.kind, color: self.color, prefix: self.prefix.clone(), suffix: self.suffix.clone()}
+    }
+}
+
+// Yellow の三つ組は最初の子を、Orange の三つ組は最後の子を優先する。
+// Green か Red の三つ組まで優先する子を辿った道を優先パスと呼び、その途中の三つ組を
+// Body に並べて、終点 (tail) とその子を直接持つ。こうすると優先パスの終点に O(1) で届く
+enum Body<T> {
+    Hole,
+    // 子が Only のとき
+    Single(Node<T>, Rc<Body<T>>),
+    // 子が Pair で、Yellow なので最初の子に続く。最後の子は別のパスになる
+    PairY(Node<T>, Rc<Body<T>>, Rc<Path<T>>),
+    // 子が Pair で、Orange なので最後の子に続く。最初の子は別のパスになる
+    PairO(Node<T>, Rc<Path<T>>, Rc<Body<T>>)
+}
+use self::Body::*;
+
+struct Path<T> {
+    body: Rc<Body<T>>,
+    tail: Node<T>,
+    child: Chain<T>
+}
+
+// 三つ組を 0 個、1 個 (Only)、2 個 (Left と Right) 並べたもの
+enum Chain<T> {
+    Empty,
+    Only(Rc<Path<T>>),
+    Pair(Rc<Path<T>>, Rc<Path<T>>)
+}
+use self::Chain::{Empty, Only, Pair};
+
+impl <T> Clone for Chain<T> {
+    fn clone(&self) -> Chain<T> {
+        match self {
+            &Empty => Empty,
+            &Only(ref p) => Only(p.clone()),
+            &Pair(ref l, ref r) => Pair(l.clone(), r.clone())
+        }
+    }
+}
+
+fn color_of_len(n: usize) -> Color {
+    if n >= 8 {
+        Green
+    } else if n == 7 {
+        Yellow
+    } else if n == 6 {
+        Orange
+    } else {
+        Red
+    }
+}
+
+// 要素数から決まる実際の色
+fn actual_color<T>(n: &Node<T>, has_child: bool) -> Color
+    where T: Clone + 'static
+{
+    if !has_child {
+        return Green
+    }
+    match n.kind {
+        Kind::Only => cmp::min(color_of_len(n.prefix.len()), color_of_len(n.suffix.len())),
+        Kind::Left => color_of_len(n.prefix.len()),
+        Kind::Right => color_of_len(n.suffix.len())
+    }
+}
+
+// pop や eject のあとの色。記録した色より 1 段しか悪くならない
+fn degrade<T>(mut n: Node<T>) -> Node<T>
+    where T: Clone + 'static
+{
+    n.color = cmp::min(n.color, actual_color(&n, true));
+    n
+}
+
+// 短い方の要素を 1 つずつ移して連結する。呼び出し側では、どちらかが常に 8 要素以下
+fn bappend<T>(a: &Buf<T>, b: &Buf<T>) -> Buf<T>
+    where T: Clone + 'static
+{
+    let (mut a, mut b) = (a.clone(), b.clone());
+    if a.len() <= b.len() {
+        while !a.is_empty() {
+            tick();
+            b = b.cons(a.last().clone());
+            a = a.init();
+        }
+        b
+    } else {
+        while !b.is_empty() {
+            tick();
+            a = a.snoc(b.head().clone());
+            b = b.tail();
+        }
+        a
+    }
+}
+
+// 先頭の 2 要素と残り
+fn split_front2<T>(b: &Buf<T>) -> (Buf<T>, Buf<T>)
+    where T: Clone + 'static
+{
+    let rest = b.tail();
+    (Buf::empty().snoc(b.head().clone()).snoc(rest.head().clone()), rest.tail())
+}
+
+// 残りと末尾の 2 要素
+fn split_back2<T>(b: &Buf<T>) -> (Buf<T>, Buf<T>)
+    where T: Clone + 'static
+{
+    let rest = b.init();
+    (rest.init(), Buf::empty().cons(b.last().clone()).cons(rest.last().clone()))
+}
+
+fn buf1<T>(x: Elem<T>) -> Buf<T>
+    where T: Clone + 'static
+{
+    Buf::empty().cons(x)
+}
+
+fn top<T>(p: &Path<T>) -> &Node<T> {
+    match *p.body {
+        Hole => &p.tail,
+        Single(ref n, _) | PairY(ref n, _, _) | PairO(ref n, _, _) => n
+    }
+}
+
+// パスの先頭の三つ組を、子とともに取り出す
+fn split<T>(p: &Path<T>) -> (Node<T>, Chain<T>) {
+    tick();
+    let rest = |body: &Rc<Body<T>>| Rc::new(Path {body: body.clone(), tail: p.tail.clone(), child: p.child.clone()});
+    match *p.body {
+        Hole => (p.tail.clone(), p.child.clone()),
+        Single(ref n, ref body) => (n.clone(), Only(rest(body))),
+        PairY(ref n, ref body, ref r) => (n.clone(), Pair(rest(body), r.clone())),
+        PairO(ref n, ref l, ref body) => (n.clone(), Pair(l.clone(), rest(body)))
+    }
+}
+
+// split の逆。n の色で、子のどのパスに続けるかを決める
+fn join<T>(n: Node<T>, c: Chain<T>) -> Rc<Path<T>> {
+    tick();
+    match (n.color, c) {
+        (Green, c) | (Red, c) => Rc::new(Path {body: Rc::new(Hole), tail: n, child: c}),
+        (_, Empty) => panic!("yellow or orange triple without a child"),
+        (_, Only(p)) => Rc::new(Path {body: Rc::new(Single(n, p.body.clone())), tail: p.tail.clone(), child: p.child.clone()}),
+        (Yellow, Pair(l, r)) => Rc::new(Path {body: Rc::new(PairY(n, l.body.clone(), r)), tail: l.tail.clone(), child: l.child.clone()}),
+        (Orange, Pair(l, r)) => Rc::new(Path {body: Rc::new(PairO(n, l, r.body.clone())), tail: r.tail.clone(), child: r.child.clone()})
+    }
+}
+
+// 優先パスの終点が Red なら直す
+fn fix<T>(p: &Rc<Path<T>>) -> Rc<Path<T>>
+    where T: Clone + 'static
+{
+    if p.tail.color != Red {
+        return p.clone()
+    }
+    let (n, c) = repair(&p.tail, &p.child);
+    Rc::new(Path {body: p.body.clone(), tail: n, child: c})
+}
+
+// Red の三つ組の注目するバッファを、子から要素を借りて 8 要素以上にし、Green にする。
+// Red の三つ組の子の優先パスは Green で終わるので、子を pop や eject で 1 段悪くしても
+// 直さなくてよい。借りた Big の子は連結でつなぎ直すが、連結も再帰しない
+fn repair<T>(n: &Node<T>, c: &Chain<T>) -> (Node<T>, Chain<T>)
+    where T: Clone + 'static
+{
+    tick();
+    let mut n = n.clone();
+    if actual_color(&n, true) == Green {
+        n.color = Green;
+        return (n, c.clone())
+    }
+    let child = match n.kind {
+        Kind::Left => {
+            let (x, c) = c.naive_pop();
+            match x {
+                Small(ref b) => {
+                    n.prefix = bappend(&n.prefix, b);
+                    c
+                },
+                Big(ref t) => {
+                    let (ref px, ref cx, ref sx) = **t;
+                    n.prefix = bappend(&n.prefix, px);
+                    cx.append(&c.push(Small(Rc::new(sx.clone()))))
+                },
+                Leaf(_) => panic!("leaf in a child deque")
+            }
+        },
+        Kind::Right => {
+            let (c, y) = c.naive_eject();
+            match y {
+                Small(ref b) => {
+                    n.suffix = bappend(b, &n.suffix);
+                    c
+                },
+                Big(ref t) => {
+                    let (ref py, ref cy, ref sy) = **t;
+                    n.suffix = bappend(sy, &n.suffix);
+                    c.inject(Small(Rc::new(py.clone()))).append(cy)
+                },
+                Leaf(_) => panic!("leaf in a child deque")
+            }
+        },
+        Kind::Only if c.is_single() => {
+            // 子の唯一の要素を両側で分ける。少なくとも片側は 8 要素未満
+            match c.head().clone() {
+                Small(ref b) => {
+                    if n.prefix.len() < 8 {
+                        n.prefix = bappend(&n.prefix, b);
+                    } else {
+                        n.suffix = bappend(b, &n.suffix);
+                    }
+                    Empty
+                },
+                Big(ref t) => {
+                    let (ref px, ref cx, ref sx) = **t;
+                    if n.prefix.len() < 8 && n.suffix.len() < 8 {
+                        n.prefix = bappend(&n.prefix, px);
+                        n.suffix = bappend(sx, &n.suffix);
+                        cx.clone()
+                    } else if n.prefix.len() < 8 {
+                        n.prefix = bappend(&n.prefix, px);
+                        cx.inject(Small(Rc::new(sx.clone())))
+                    } else {
+                        n.suffix = bappend(sx, &n.suffix);
+                        cx.push(Small(Rc::new(px.clone())))
+                    }
+                },
+                Leaf(_) => panic!("leaf in a child deque")
+            }
+        },
+        Kind::Only => {
+            let (front, c) = if n.prefix.len() < 8 {
+                let (x, c) = c.naive_pop();
+                match x {
+                    Small(ref b) => {
+                        n.prefix = bappend(&n.prefix, b);
+                        (Empty, c)
+                    },
+                    Big(ref t) => {
+                        let (ref px, ref cx, ref sx) = **t;
+                        n.prefix = bappend(&n.prefix, px);
+                        (cx.inject(Small(Rc::new(sx.clone()))), c)
+                    },
+                    Leaf(_) => panic!("leaf in a child deque")
+                }
+            } else {
+                (Empty, c.clone())
+            };
+            // 子は 2 要素以上あったので、pop のあとも空ではない
+            let (c, back) = if n.suffix.len() < 8 {
+                let (c, y) = c.naive_eject();
+                match y {
+                    Small(ref b) => {
+                        n.suffix = bappend(b, &n.suffix);
+                        (c, Empty)
+                    },
+                    Big(ref t) => {
+                        let (ref py, ref cy, ref sy) = **t;
+                        n.suffix = bappend(sy, &n.suffix);
+                        (c, cy.push(Small(Rc::new(py.clone()))))
+                    },
+                    Leaf(_) => panic!("leaf in a child deque")
+                }
+            } else {
+                (c, Empty)
+            };
+            front.append(&c).append(&back)
+        }
+    };
+    n.color = Green;
+    (n, child)
+}
+
+impl <T> Chain<T>
+    where T: Clone + 'static
+{
+    fn is_empty(&self) -> bool {
+        match self {
+            &Empty => true,
+            _ => false
+        }
+    }
+
+    // 子を持たない Only で、要素が 1 つだけ
+    fn is_single(&self) -> bool {
+        match self {
+            &Only(ref p) => match *p.body {
+                Hole => p.child.is_empty() && p.tail.prefix.len() + p.tail.suffix.len() == 1,
+                _ => false
+            },
+            _ => false
+        }
+    }
+
+    // 子を持たない Only で、要素が 8 未満。連結では要素を 1 つずつ移す
+    fn is_small(&self) -> bool {
+        match self {
+            &Only(ref p) => match *p.body {
+                Hole => p.child.is_empty() && p.tail.prefix.len() + p.tail.suffix.len() < 8,
+                _ => false
+            },
+            _ => false
+        }
+    }
+
+    fn head(&self) -> &Elem<T> {
+        match self {
+            &Empty => panic!("empty deque"),
+            &Only(ref p) => {
+                let n = top(p);
+                if n.prefix.is_empty() { n.suffix.head() } else { n.prefix.head() }
+            },
+            &Pair(ref l, _) => top(l).prefix.head()
+        }
+    }
+
+    fn last(&self) -> &Elem<T> {
+        match self {
+            &Empty => panic!("empty deque"),
+            &Only(ref p) => {
+                let n = top(p);
+                if n.suffix.is_empty() { n.prefix.last() } else { n.suffix.last() }
+            },
+            &Pair(_, ref r) => top(r).suffix.last()
+        }
+    }
+
+    // push と inject は要素数を増やすだけなので、色も優先パスも変わらない
+    fn push(&self, x: Elem<T>) -> Chain<T> {
+        match self {
+            &Empty => Only(join(Node {kind: Kind::Only, color: Green, prefix: buf1(x), suffix: Buf::empty()}, Empty)),
+            &Only(ref p) => {
+                let (mut n, c) = split(p);
+                n.prefix = n.prefix.cons(x);
+                Only(join(n, c))
+            },
+            &Pair(ref l, ref r) => {
+                let (mut n, c) = split(l);
+                n.prefix = n.prefix.cons(x);
+                Pair(join(n, c), r.clone())
+            }
+        }
+    }
+
+    fn inject(&self, x: Elem<T>) -> Chain<T> {
+        match self {
+            &Empty => Only(join(Node {kind: Kind::Only, color: Green, prefix: buf1(x), suffix: Buf::empty()}, Empty)),
+            &Only(ref p) => {
+                let (mut n, c) = split(p);
+                n.suffix = n.suffix.snoc(x);
+                Only(join(n, c))
+            },
+            &Pair(ref l, ref r) => {
+                let (mut n, c) = split(r);
+                n.suffix = n.suffix.snoc(x);
+                Pair(l.clone(), join(n, c))
+            }
+        }
+    }
+
+    // 先頭の三つ組から 1 要素取り除く。先頭の三つ組は Red でないので、色は高々 1 段悪くなるだけ。
+    // 子を持たない Left が 5 要素を割ったら、隣の Right と 1 つの Only にまとめる
+    fn naive_pop(&self) -> (Elem<T>, Chain<T>) {
+        match self {
+            &Empty => panic!("empty deque"),
+            &Only(ref p) => {
+                let (mut n, c) = split(p);
+                if !c.is_empty() {
+                    let x = n.prefix.head().clone();
+                    n.prefix = n.prefix.tail();
+                    (x, Only(join(degrade(n), c)))
+                } else if !n.prefix.is_empty() {
+                    let x = n.prefix.head().clone();
+                    n.prefix = n.prefix.tail();
+                    (x, if n.prefix.is_empty() && n.suffix.is_empty() { Empty } else { Only(join(n, c)) })
+                } else {
+                    let x = n.suffix.head().clone();
+                    n.suffix = n.suffix.tail();
+                    (x, if n.suffix.is_empty() { Empty } else { Only(join(n, c)) })
+                }
+            },
+            &Pair(ref l, ref r) => {
+                let (mut n, c) = split(l);
+                let x = n.prefix.head().clone();
+                n.prefix = n.prefix.tail();
+                if !c.is_empty() {
+                    (x, Pair(join(degrade(n), c), r.clone()))
+                } else if n.prefix.len() >= 5 {
+                    (x, Pair(join(n, c), r.clone()))
+                } else {
+                    let (m, mc) = split(r);
+                    let prefix = bappend(&bappend(&n.prefix, &n.suffix), &m.prefix);
+                    let color = if mc.is_empty() { Green } else { m.color };
+                    (x, Only(join(Node {kind: Kind::Only, color, prefix, suffix: m.suffix}, mc)))
+                }
+            }
+        }
+    }
+
+    fn naive_eject(&self) -> (Chain<T>, Elem<T>) {
+        match self {
+            &Empty => panic!("empty deque"),
+            &Only(ref p) => {
+                let (mut n, c) = split(p);
+                if !c.is_empty() {
+                    let y = n.suffix.last().clone();
+                    n.suffix = n.suffix.init();
+                    (Only(join(degrade(n), c)), y)
+                } else if !n.suffix.is_empty() {
+                    let y = n.suffix.last().clone();
+                    n.suffix = n.suffix.init();
+                    (if n.prefix.is_empty() && n.suffix.is_empty() { Empty } else { Only(join(n, c)) }, y)
+                } else {
+                    let y = n.prefix.last().clone();
+                    n.prefix = n.prefix.init();
+                    (if n.prefix.is_empty() { Empty } else { Only(join(n, c)) }, y)
+                }
+            },
+            &Pair(ref l, ref r) => {
+                let (mut n, c) = split(r);
+                let y = n.suffix.last().clone();
+                n.suffix = n.suffix.init();
+                if !c.is_empty() {
+                    (Pair(l.clone(), join(degrade(n), c)), y)
+                } else if n.suffix.len() >= 5 {
+                    (Pair(l.clone(), join(n, c)), y)
+                } else {
+                    let (m, mc) = split(l);
+                    let suffix = bappend(&m.suffix, &bappend(&n.prefix, &n.suffix));
+                    let color = if mc.is_empty() { Green } else { m.color };
+                    (Only(join(Node {kind: Kind::Only, color, prefix: m.prefix, suffix}, mc)), y)
+                }
+            }
+        }
+    }
+
+    // 最上段の pop。最初の優先パスが Red で終わったら、その終点だけを直す
+    fn pop(&self) -> Chain<T> {
+        match self.naive_pop().1 {
+            Empty => Empty,
+            Only(p) => Only(fix(&p)),
+            Pair(l, r) => Pair(fix(&l), r)
+        }
+    }
+
+    fn eject(&self) -> Chain<T> {
+        match self.naive_eject().0 {
+            Empty => Empty,
+            Only(p) => Only(fix(&p)),
+            Pair(l, r) => Pair(l, fix(&r))
+        }
+    }
+
+    // 連結の左側になる Left の三つ組。色と優先パスは元の最初の三つ組のものを引き継ぐ
+    fn as_left(&self) -> (Node<T>, Chain<T>) {
+        match self {
+            &Empty => panic!("empty deque"),
+            &Only(ref p) => {
+                let (n, c) = split(p);
+                if !c.is_empty() {
+                    let (rest, last2) = split_back2(&n.suffix);
+                    (Node {kind: Kind::Left, color: n.color, prefix: n.prefix, suffix: last2}, c.inject(Small(Rc::new(rest))))
+                } else if n.prefix.len() < 8 || n.suffix.len() < 8 {
+                    let (rest, last2) = split_back2(&bappend(&n.prefix, &n.suffix));
+                    (Node {kind: Kind::Left, color: Green, prefix: rest, suffix: last2}, Empty)
+                } else {
+                    let (rest, last2) = split_back2(&n.suffix);
+                    (Node {kind: Kind::Left, color: Green, prefix: n.prefix, suffix: last2}, Empty.push(Small(Rc::new(rest))))
+                }
+            },
+            &Pair(ref l, ref r) => {
+                let (ln, lc) = split(l);
+                let (rn, rc) = split(r);
+                let (rest, last2) = split_back2(&rn.suffix);
+                if lc.is_empty() {
+                    let prefix = bappend(&ln.prefix, &bappend(&ln.suffix, &rn.prefix));
+                    (Node {kind: Kind::Left, color: Green, prefix, suffix: last2}, rc.inject(Small(Rc::new(rest))))
+                } else {
+                    let mid = bappend(&ln.suffix, &rn.prefix);
+                    let big = Big(Rc::new((mid, rc, rest)));
+                    (Node {kind: Kind::Left, color: ln.color, prefix: ln.prefix, suffix: last2}, lc.inject(big))
+                }
+            }
+        }
+    }
+
+    // 連結の右側になる Right の三つ組
+    fn as_right(&self) -> (Node<T>, Chain<T>) {
+        match self {
+            &Empty => panic!("empty deque"),
+            &Only(ref p) => {
+                let (n, c) = split(p);
+                if !c.is_empty() {
+                    let (first2, rest) = split_front2(&n.prefix);
+                    (Node {kind: Kind::Right, color: n.color, prefix: first2, suffix: n.suffix}, c.push(Small(Rc::new(rest))))
+                } else if n.prefix.len() < 8 || n.suffix.len() < 8 {
+                    let (first2, rest) = split_front2(&bappend(&n.prefix, &n.suffix));
+                    (Node {kind: Kind::Right, color: Green, prefix: first2, suffix: rest}, Empty)
+                } else {
+                    let (first2, rest) = split_front2(&n.prefix);
+                    (Node {kind: Kind::Right, color: Green, prefix: first2, suffix: n.suffix}, Empty.push(Small(Rc::new(rest))))
+                }
+            },
+            &Pair(ref l, ref r) => {
+                let (ln, lc) = split(l);
+                let (rn, rc) = split(r);
+                let (first2, rest) = split_front2(&ln.prefix);
+                if rc.is_empty() {
+                    let suffix = bappend(&bappend(&ln.suffix, &rn.prefix), &rn.suffix);
+                    (Node {kind: Kind::Right, color: Green, prefix: first2, suffix}, lc.push(Small(Rc::new(rest))))
+                } else {
+                    let mid = bappend(&ln.suffix, &rn.prefix);
+                    let big = Big(Rc::new((rest, lc, mid)));
+                    (Node {kind: Kind::Right, color: rn.color, prefix: first2, suffix: rn.suffix}, rc.push(big))
+                }
+            }
+        }
+    }
+
+    // 片方が小さければ要素を 1 つずつ移す。そうでなければ、左を Left、右を Right にして並べる。
+    // どちらも下の段への push か inject を 1 回するだけなので、再帰しない
+    fn append(&self, other: &Chain<T>) -> Chain<T> {
+        if self.is_empty() {
+            other.clone()
+        } else if other.is_empty() {
+            self.clone()
+        } else if self.is_small() {
+            let mut d = self.clone();
+            let mut other = other.clone();
+            while !d.is_empty() {
+                tick();
+                let (rest, y) = d.naive_eject();
+                other = other.push(y);
+                d = rest;
+            }
+            other
+        } else if other.is_small() {
+            let mut d = other.clone();
+            let mut this = self.clone();
+            while !d.is_empty() {
+                tick();
+                let (x, rest) = d.naive_pop();
+                this = this.inject(x);
+                d = rest;
+            }
+            this
+        } else {
+            let (ln, lc) = self.as_left();
+            let (rn, rc) = other.as_right();
+            Pair(join(ln, lc), join(rn, rc))
+        }
+    }
+}
+
+// cons, snoc, head, last, tail, init, append はすべて最悪 O(1)
+pub struct RealTimeCatenableDeque<T>(Chain<T>);
+
+impl <T> Clone for RealTimeCatenableDeque<T> {
+    fn clone(&self) -> RealTimeCatenableDeque<T> {
+        RealTimeCatenableDeque(self.0.clone())
+    }
+}
+
+fn leaf<T>(x: &Elem<T>) -> &T {
+    match x {
+        &Leaf(ref x) => x,
+        _ => panic!("stored triple in the top-level deque")
+    }
+}
+
+impl <T> Deque<T> for RealTimeCatenableDeque<T>
+    where T: Clone + 'static
+{
+    fn empty() -> RealTimeCatenableDeque<T> {
+        RealTimeCatenableDeque(Empty)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn cons(&self, x: T) -> RealTimeCatenableDeque<T> {
+        RealTimeCatenableDeque(self.0.push(Leaf(x)))
+    }
+
+    fn head(&self) -> &T {
+        leaf(self.0.head())
+    }
+
+    fn tail(&self) -> RealTimeCatenableDeque<T> {
+        RealTimeCatenableDeque(self.0.pop())
+    }
+
+    fn snoc(&self, x: T) -> RealTimeCatenableDeque<T> {
+        RealTimeCatenableDeque(self.0.inject(Leaf(x)))
+    }
+
+    fn last(&self) -> &T {
+        leaf(self.0.last())
+    }
+
+    fn init(&self) -> RealTimeCatenableDeque<T> {
+        RealTimeCatenableDeque(self.0.eject())
+    }
+}
+
+impl <T> CatenableDeque<T> for RealTimeCatenableDeque<T>
+    where T: Clone + 'static
+{
+    fn append(&self, other: &RealTimeCatenableDeque<T>) -> RealTimeCatenableDeque<T> {
+        RealTimeCatenableDeque(self.0.append(&other.0))
+    }
+}
+
+impl <T> fmt::Debug for RealTimeCatenableDeque<T>
+    where T: fmt::Debug + Clone + 'static
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut d = self.clone();
+        write!(f, "[")?;
+        while !d.is_empty() {
+            write!(f, "{:?}", d.head())?;
+            d = d.tail();
+            if !d.is_empty() { write!(f, ", ")? }
+        }
+        write!(f, "]")
+    }
+}
+
+// 三つ組を開いたときの、最初の子と最後の子
+fn children<T>(c: &Chain<T>) -> Option<(Rc<Path<T>>, Rc<Path<T>>)> {
+    match c {
+        &Empty => None,
+        &Only(ref p) => Some((p.clone(), p.clone())),
+        &Pair(ref l, ref r) => Some((l.clone(), r.clone()))
+    }
+}
+
+// 優先パスの終点の色
+fn path_end<T>(p: &Path<T>) -> Color {
+    p.tail.color
+}
+
+fn check_buf<T>(b: &Buf<T>, level: usize) -> Result<(), InvariantError>
+    where T: Clone + 'static
+{
+    let mut b = b.clone();
+    while !b.is_empty() {
+        check_elem(b.head(), level)?;
+        b = b.tail();
+    }
+    Ok(())
+}
+
+fn check_elem<T>(x: &Elem<T>, level: usize) -> Result<(), InvariantError>
+    where T: Clone + 'static
+{
+    // 段 k の格納された三つ組は、段 k - 1 の要素のバッファと段 k の子を持つ
+    match (x, level) {
+        (&Leaf(_), 0) => Ok(()),
+        (&Small(ref b), k) if k > 0 => {
+            if b.len() < 3 {
+                return Err(InvariantError::Size(format!("small stored triple with {} elements", b.len())))
+            }
+            check_buf(b, k - 1)
+        },
+        (&Big(ref t), k) if k > 0 => {
+            let (ref p, ref c, ref s) = **t;
+            if p.len() < 3 || s.len() < 3 {
+                return Err(InvariantError::Size(format!("stored triple with buffers of {} and {}", p.len(), s.len())))
+            }
+            check_buf(p, k - 1)?;
+            check_buf(s, k - 1)?;
+            check_chain(c, k)
+        },
+        _ => Err(InvariantError::Size("element on the wrong level".to_string()))
+    }
+}
+
+// パスの先頭の三つ組の色が Body の形と合っていることと、三つ組の不変条件を調べる。
+// split で開いた子は、先頭からのパスに組み直されている
+fn check_path<T>(p: &Path<T>, level: usize) -> Result<(), InvariantError>
+    where T: Clone + 'static
+{
+    let color = top(p).color;
+    match *p.body {
+        Hole if color == Yellow || color == Orange =>
+            return Err(InvariantError::Color(format!("{:?} triple at the end of a preferred path", color))),
+        Single(_, _) | PairY(_, _, _) | PairO(_, _, _) if color == Green || color == Red =>
+            return Err(InvariantError::Color(format!("{:?} triple inside a preferred path", color))),
+        PairY(_, _, _) if color != Yellow =>
+            return Err(InvariantError::Color("preferred path of an orange triple goes left".to_string())),
+        PairO(_, _, _) if color != Orange =>
+            return Err(InvariantError::Color("preferred path of a yellow triple goes right".to_string())),
+        _ => ()
+    }
+    let (n, c) = split(p);
+    check_node(&n, &c, level)?;
+    check_chain(&c, level + 1)
+}
+
+fn check_node<T>(n: &Node<T>, c: &Chain<T>, level: usize) -> Result<(), InvariantError>
+    where T: Clone + 'static
+{
+    let (p, s) = (n.prefix.len(), n.suffix.len());
+    let sizes_ok = match (n.kind, c.is_empty()) {
+        (Kind::Only, true) => p + s >= 1,
+        (Kind::Only, false) => p >= 5 && s >= 5,
+        (Kind::Left, _) => p >= 5 && s == 2,
+        (Kind::Right, _) => p == 2 && s >= 5
+    };
+    if !sizes_ok {
+        return Err(InvariantError::Size(format!("{:?} triple with buffers of {} and {}", n.kind, p, s)))
+    }
+    if n.color > actual_color(n, !c.is_empty()) {
+        return Err(InvariantError::Color(format!("{:?} triple recorded better than its sizes", n.color)))
+    }
+    check_buf(&n.prefix, level)?;
+    check_buf(&n.suffix, level)?;
+    match (n.color, children(c)) {
+        (Red, Some((first, last))) => {
+            if path_end(&first) != Green || path_end(&last) != Green {
+                return Err(InvariantError::Color("a child of a red triple has a red preferred path".to_string()))
+            }
+        },
+        (Orange, Some((first, _))) => {
+            if path_end(&first) != Green {
+                return Err(InvariantError::Color("the first child of an orange triple has a red preferred path".to_string()))
+            }
+        },
+        _ => ()
+    }
+    Ok(())
+}
+
+// 段 level の要素を持つ三つ組の並びを調べる。
+// 最上段の優先パスはどれも Green で終わる。下の段では Red で終わってもよい
+fn check_chain<T>(c: &Chain<T>, level: usize) -> Result<(), InvariantError>
+    where T: Clone + 'static
+{
+    let paths = match c {
+        &Empty => vec![],
+        &Only(ref p) => vec![p.clone()],
+        &Pair(ref l, ref r) => {
+            if top(l).kind != Kind::Left || top(r).kind != Kind::Right {
+                return Err(InvariantError::Size("pair of triples that are not left and right".to_string()))
+            }
+            vec![l.clone(), r.clone()]
+        }
+    };
+    for p in paths {
+        if level == 0 && path_end(&p) != Green {
+            return Err(InvariantError::Color("top-level preferred path ends red".to_string()))
+        }
+        if let &Only(_) = c {
+            if top(&p).kind != Kind::Only {
+                return Err(InvariantError::Size("single triple that is not an only triple".to_string()))
+            }
+        }
+        check_path(&p, level)?;
+    }
+    Ok(())
+}
+
+impl <T> Validate for RealTimeCatenableDeque<T>
+    where T: Clone + 'static
+{
+    fn check_invariants(&self) -> Result<(), InvariantError> {
+        check_chain(&self.0, 0)
+    }
+}
@@ -0,0 +1,204 @@
+/// 8.4.3 リアルタイム両端キュー
+use std::fmt;
+
+use super::{Stream, Deque};
+use super::stream::StreamCell::*;
+
+// rotate_drop が一度に front から取り除く要素数。2 か 3 でなければならない
+const C: usize = 3;
+
+// front と rear それぞれのスケジュールを 1 操作ごとに 1 つか 2 つ進めることで、
+// 回転のコストをすべての操作に分散させる
+pub struct RealTimeDeque<T> {
+    lenf: usize,
+    front: Stream<T>,
+    sf: Stream<T>,
+    lenr: usize,
+    rear: Stream<T>,
+    sr: Stream<T>
+}
+
+impl <T> Clone for RealTimeDeque<T> {
+    fn clone(&self) -> RealTimeDeque<T> {
+        RealTimeDeque {
+            lenf: self.lenf,
+            front: self.front.clone(),
+            sf: self.sf.clone(),
+            lenr: self.lenr,
+            rear: self.rear.clone(),
+            sr: self.sr.clone()
+        }
+    }
+}
+
+fn exec1<T>(s: &Stream<T>) -> Stream<T>
+    where T: Clone + 'static
+{
+    match s.force() {
+        &Nil => s.clone(),
+        &Cons(_, ref s) => s.clone()
+    }
+}
+
+fn exec2<T>(s: &Stream<T>) -> Stream<T>
+    where T: Clone + 'static
+{
+    exec1(&exec1(s))
+}
+
+// drop をその場で評価する。遅延させたままにすると drop の連鎖ができてしまい、
+// 最後にまとめて評価するときに O(1) で済まなくなる
+fn drop_now<T>(f: &Stream<T>, n: usize) -> Stream<T>
+    where T: Clone + 'static
+{
+    let s = f.drop(n);
+    s.force();
+    s
+}
+
+// r ++ reverse(f) ++ a を、f から C 個ずつ取り出しながら作る
+fn rotate_rev<T>(r: Stream<T>, f: Stream<T>, a: Stream<T>) -> Stream<T>
+    where T: Clone + 'static
+{
+    Stream::lazy(move || {
+        match r.force() {
+            &Nil => f.reverse().append(&a).force().clone(),
+            &Cons(ref x, ref r) => {
+                Cons(x.clone(), rotate_rev(r.clone(), drop_now(&f, C), f.take(C).reverse().append(&a)))
+            }
+        }
+    })
+}
+
+// r ++ reverse(drop(i, f)) を、f から C 個ずつ取り除きながら作る
+fn rotate_drop<T>(r: Stream<T>, i: usize, f: Stream<T>) -> Stream<T>
+    where T: Clone + 'static
+{
+    Stream::lazy(move || {
+        if i < C {
+            rotate_rev(r, drop_now(&f, i), Stream::empty()).force().clone()
+        } else {
+            match r.force() {
+                &Nil => panic!("rotate_drop: rear is shorter than expected"),
+                &Cons(ref x, ref r) => Cons(x.clone(), rotate_drop(r.clone(), i - C, drop_now(&f, C)))
+            }
+        }
+    })
+}
+
+impl <T> RealTimeDeque<T>
+    where T: Clone + 'static
+{
+    pub fn len(&self) -> usize {
+        self.lenf + self.lenr
+    }
+
+    fn check(lenf: usize, front: Stream<T>, sf: Stream<T>,
+             lenr: usize, rear: Stream<T>, sr: Stream<T>) -> RealTimeDeque<T> {
+        if lenf > C * lenr + 1 {
+            let i = (lenf + lenr) / 2;
+            let j = lenf + lenr - i;
+            let f = front.take(i);
+            let r = rotate_drop(rear, i, front);
+            RealTimeDeque {lenf: i, front: f.clone(), sf: f, lenr: j, rear: r.clone(), sr: r}
+        } else if lenr > C * lenf + 1 {
+            let j = (lenf + lenr) / 2;
+            let i = lenf + lenr - j;
+            let r = rear.take(j);
+            let f = rotate_drop(front, j, rear);
+            RealTimeDeque {lenf: i, front: f.clone(), sf: f, lenr: j, rear: r.clone(), sr: r}
+        } else {
+            RealTimeDeque {lenf, front, sf, lenr, rear, sr}
+        }
+    }
+}
+
+impl <T> Deque<T> for RealTimeDeque<T>
+    where T: Clone + 'static
+{
+    fn empty() -> RealTimeDeque<T> {
+        RealTimeDeque {
+            lenf: 0,
+            front: Stream::empty(),
+            sf: Stream::empty(),
+            lenr: 0,
+            rear: Stream::empty(),
+            sr: Stream::empty()
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.lenf + self.lenr == 0
+    }
+
+    fn cons(&self, x: T) -> RealTimeDeque<T> {
+        RealTimeDeque::check(self.lenf + 1, Stream::cons(x, self.front.clone()), exec1(&self.sf),
+                             self.lenr, self.rear.clone(), exec1(&self.sr))
+    }
+
+    // 不変条件より、front が空なら rear の要素は高々 1 つ
+    fn head(&self) -> &T {
+        if self.lenf == 0 {
+            if self.lenr == 0 {
+                panic!("empty deque")
+            }
+            self.rear.head()
+        } else {
+            self.front.head()
+        }
+    }
+
+    fn tail(&self) -> RealTimeDeque<T> {
+        if self.lenf == 0 {
+            if self.lenr == 0 {
+                panic!("empty deque")
+            }
+            Deque::empty()
+        } else {
+            RealTimeDeque::check(self.lenf - 1, self.front.tail().clone(), exec2(&self.sf),
+                                 self.lenr, self.rear.clone(), exec2(&self.sr))
+        }
+    }
+
+    fn snoc(&self, x: T) -> RealTimeDeque<T> {
+        RealTimeDeque::check(self.lenf, self.front.clone(), exec1(&self.sf),
+                             self.lenr + 1, Stream::cons(x, self.rear.clone()), exec1(&self.sr))
+    }
+
+    fn last(&self) -> &T {
+        if self.lenr == 0 {
+            if self.lenf == 0 {
+                panic!("empty deque")
+            }
+            self.front.head()
+        } else {
+            self.rear.head()
+        }
+    }
+
+    fn init(&self) -> RealTimeDeque<T> {
+        if self.lenr == 0 {
+            if self.lenf == 0 {
+                panic!("empty deque")
+            }
+            Deque::empty()
+        } else {
+            RealTimeDeque::check(self.lenf, self.front.clone(), exec2(&self.sf),
+                                 self.lenr - 1, self.rear.tail().clone(), exec2(&self.sr))
+        }
+    }
+}
+
+impl <T> fmt::Debug for RealTimeDeque<T>
+    where T: fmt::Debug + Clone + 'static
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rear = self.rear.reverse();
+        write!(f, "[")?;
+        for (i, v) in self.front.iter().chain(rear.iter()).enumerate() {
+            if i != 0 { write!(f, ", ")? }
+            write!(f, "{:?}", v)?;
+        }
+        write!(f, "]")
+    }
+}
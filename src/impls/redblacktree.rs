@@ -3,7 +3,7 @@ use std::fmt;
 use std::cmp::Ordering::*;
 use std::boxed::FnBox;
 
-use super::{Set, Stack, List};
+use super::{Set, Stack, List, Validate, InvariantError};
 
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum Color {
@@ -14,19 +14,35 @@ pub enum Color {
 #[derive(Clone)]
 pub enum RedBlackTree<T> {
     Empty,
-    Node(Color, Rc<RedBlackTree<T>>, T, Rc<RedBlackTree<T>>)
+    // 最後の要素は部分木の要素数
+    Node(Color, Rc<RedBlackTree<T>>, T, Rc<RedBlackTree<T>>, usize)
 }
 
 use self::Color::*;
 use self::RedBlackTree::*;
 
+// 子の要素数から自分の要素数を求めて節点を作る
+fn node<T>(color: Color, left: Rc<RedBlackTree<T>>, val: T, right: Rc<RedBlackTree<T>>) -> RedBlackTree<T> {
+    let size = left.size() + right.size() + 1;
+    Node(color, left, val, right, size)
+}
+
+impl <T> RedBlackTree<T> {
+    fn size(&self) -> usize {
+        match self {
+            &Empty => 0,
+            &Node(_, _, _, _, size) => size
+        }
+    }
+}
+
 impl <T> fmt::Debug for RedBlackTree<T>
     where T: fmt::Debug
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             &Empty => write!(f, "E"),
-            &Node(color, ref left, ref val, ref right) => {
+            &Node(color, ref left, ref val, ref right, _) => {
                 let c = if color == Red { "R" } else { "B" };
                 write!(f, "T({}, {:?}, {:?}, {:?})", c, left, val, right)
             }
@@ -55,40 +71,40 @@ impl <T> RedBlackTree<T>
     #[allow(dead_code)]
     fn balance(color: Color, left: &Rc<RedBlackTree<T>>, val: &T, right: &Rc<RedBlackTree<T>>) -> RedBlackTree<T> {
         RedBlackTree::balance_impl(color, left, val, right)
-            .unwrap_or(Node(color, left.clone(), val.clone(), right.clone()))
+            .unwrap_or(node(color, left.clone(), val.clone(), right.clone()))
     }
 
     #[allow(dead_code)]
     fn balance_impl(color: Color, left: &Rc<RedBlackTree<T>>, val: &T, right: &Rc<RedBlackTree<T>>) -> Option<RedBlackTree<T>> {
         if color == Red { return None }
-        if let &Node(Red, ref ll, ref lv, ref lr) = left.as_ref() {
-            if let &Node(Red, ref lll, ref llv, ref llr) = ll.as_ref() {
-                Some(Node(Red,
-                          Rc::new(Node(Black, lll.clone(), llv.clone(), llr.clone())),
-                          lv.clone(),
-                          Rc::new(Node(Black, lr.clone(), val.clone(), right.clone()))
-                         ))
-            } else if let &Node(Red, ref lrl, ref lrv, ref lrr) = lr.as_ref() {
-                Some(Node(Red,
-                          Rc::new(Node(Black, ll.clone(), lv.clone(), lrl.clone())),
-                          lrv.clone(),
-                          Rc::new(Node(Black, lrr.clone(), val.clone(), right.clone()))
-                         ))
-            } else {
-                None
+        if let &Node(Red, ref ll, ref lv, ref lr, _) = left.as_ref() {
+            if let &Node(Red, ref lll, ref llv, ref llr, _) = ll.as_ref() {
+                return Some(node(Red,
+                                 Rc::new(node(Black, lll.clone(), llv.clone(), llr.clone())),
+                                 lv.clone(),
+                                 Rc::new(node(Black, lr.clone(), val.clone(), right.clone()))
+                                ))
+            } else if let &Node(Red, ref lrl, ref lrv, ref lrr, _) = lr.as_ref() {
+                return Some(node(Red,
+                                 Rc::new(node(Black, ll.clone(), lv.clone(), lrl.clone())),
+                                 lrv.clone(),
+                                 Rc::new(node(Black, lrr.clone(), val.clone(), right.clone()))
+                                ))
             }
-        } else if let &Node(Red, ref rl, ref rv, ref rr) = right.as_ref() {
-            if let &Node(Red, ref rll, ref rlv, ref rlr) = rl.as_ref() {
-                Some(Node(Red,
-                          Rc::new(Node(Black, left.clone(), val.clone(), rll.clone())),
+        }
+        // 左の子が赤でも孫が黒なら、右側の場合を調べる
+        if let &Node(Red, ref rl, ref rv, ref rr, _) = right.as_ref() {
+            if let &Node(Red, ref rll, ref rlv, ref rlr, _) = rl.as_ref() {
+                Some(node(Red,
+                          Rc::new(node(Black, left.clone(), val.clone(), rll.clone())),
                           rlv.clone(),
-                          Rc::new(Node(Black, rlr.clone(), rv.clone(), rr.clone()))
+                          Rc::new(node(Black, rlr.clone(), rv.clone(), rr.clone()))
                          ))
-            } else if let &Node(Red, ref rrl, ref rrv, ref rrr) = rr.as_ref() {
-                Some(Node(Red,
-                          Rc::new(Node(Black, left.clone(), val.clone(), rl.clone())),
+            } else if let &Node(Red, ref rrl, ref rrv, ref rrr, _) = rr.as_ref() {
+                Some(node(Red,
+                          Rc::new(node(Black, left.clone(), val.clone(), rl.clone())),
                           rv.clone(),
-                          Rc::new(Node(Black, rrl.clone(), rrv.clone(), rrr.clone()))
+                          Rc::new(node(Black, rrl.clone(), rrv.clone(), rrr.clone()))
                          ))
             } else {
                 None
@@ -100,111 +116,111 @@ impl <T> RedBlackTree<T>
 
     #[allow(dead_code)]
     fn lbalance(color: Color, left: &Rc<RedBlackTree<T>>, val: &T, right: &Rc<RedBlackTree<T>>) -> RedBlackTree<T> {
-        if color == Red {
-            if let &Node(Red, ref ll, ref lv, ref lr) = left.as_ref() {
-                if let &Node(Red, ref lll, ref llv, ref llr) = ll.as_ref() {
-                    return Node(Red,
-                                Rc::new(Node(Black, lll.clone(), llv.clone(), llr.clone())),
+        if color == Black {
+            if let &Node(Red, ref ll, ref lv, ref lr, _) = left.as_ref() {
+                if let &Node(Red, ref lll, ref llv, ref llr, _) = ll.as_ref() {
+                    return node(Red,
+                                Rc::new(node(Black, lll.clone(), llv.clone(), llr.clone())),
                                 lv.clone(),
-                                Rc::new(Node(Black, lr.clone(), val.clone(), right.clone())))
-                } else if let &Node(Red, ref lrl, ref lrv, ref lrr) = lr.as_ref() {
-                    return Node(Red,
-                                Rc::new(Node(Black, ll.clone(), lv.clone(), lrl.clone())),
+                                Rc::new(node(Black, lr.clone(), val.clone(), right.clone())))
+                } else if let &Node(Red, ref lrl, ref lrv, ref lrr, _) = lr.as_ref() {
+                    return node(Red,
+                                Rc::new(node(Black, ll.clone(), lv.clone(), lrl.clone())),
                                 lrv.clone(),
-                                Rc::new(Node(Black, lrr.clone(), val.clone(), right.clone())))
+                                Rc::new(node(Black, lrr.clone(), val.clone(), right.clone())))
                 }
             }
         }
-        Node(color, left.clone(), val.clone(), right.clone())
+        node(color, left.clone(), val.clone(), right.clone())
     }
 
     #[allow(dead_code)]
     fn rbalance(color: Color, left: &Rc<RedBlackTree<T>>, val: &T, right: &Rc<RedBlackTree<T>>) -> RedBlackTree<T> {
-        if color == Red {
-            if let &Node(Red, ref rl, ref rv, ref rr) = right.as_ref() {
-                if let &Node(Red, ref rll, ref rlv, ref rlr) = rl.as_ref() {
-                    return Node(Red,
-                                Rc::new(Node(Black, left.clone(), val.clone(), rll.clone())),
+        if color == Black {
+            if let &Node(Red, ref rl, ref rv, ref rr, _) = right.as_ref() {
+                if let &Node(Red, ref rll, ref rlv, ref rlr, _) = rl.as_ref() {
+                    return node(Red,
+                                Rc::new(node(Black, left.clone(), val.clone(), rll.clone())),
                                 rlv.clone(),
-                                Rc::new(Node(Black, rlr.clone(), rv.clone(), rr.clone())))
-                } else if let &Node(Red, ref rrl, ref rrv, ref rrr) = rr.as_ref() {
-                    return Node(Red,
-                                Rc::new(Node(Black, left.clone(), val.clone(), rl.clone())),
+                                Rc::new(node(Black, rlr.clone(), rv.clone(), rr.clone())))
+                } else if let &Node(Red, ref rrl, ref rrv, ref rrr, _) = rr.as_ref() {
+                    return node(Red,
+                                Rc::new(node(Black, left.clone(), val.clone(), rl.clone())),
                                 rv.clone(),
-                                Rc::new(Node(Black, rrl.clone(), rrv.clone(), rrr.clone())))
+                                Rc::new(node(Black, rrl.clone(), rrv.clone(), rrr.clone())))
                 }
             }
         }
-        Node(color, left.clone(), val.clone(), right.clone())
+        node(color, left.clone(), val.clone(), right.clone())
     }
 
     fn llbalance(color: Color, left: &Rc<RedBlackTree<T>>, val: &T, right: &Rc<RedBlackTree<T>>) -> RedBlackTree<T> {
-        if color == Red {
-            if let &Node(Red, ref ll, ref lv, ref lr) = left.as_ref() {
-                if let &Node(Red, ref lll, ref llv, ref llr) = ll.as_ref() {
-                    return Node(Red,
-                                Rc::new(Node(Black, lll.clone(), llv.clone(), llr.clone())),
+        if color == Black {
+            if let &Node(Red, ref ll, ref lv, ref lr, _) = left.as_ref() {
+                if let &Node(Red, ref lll, ref llv, ref llr, _) = ll.as_ref() {
+                    return node(Red,
+                                Rc::new(node(Black, lll.clone(), llv.clone(), llr.clone())),
                                 lv.clone(),
-                                Rc::new(Node(Black, lr.clone(), val.clone(), right.clone())))
+                                Rc::new(node(Black, lr.clone(), val.clone(), right.clone())))
                 }
             }
         }
-        Node(color, left.clone(), val.clone(), right.clone())
+        node(color, left.clone(), val.clone(), right.clone())
     }
 
     fn lrbalance(color: Color, left: &Rc<RedBlackTree<T>>, val: &T, right: &Rc<RedBlackTree<T>>) -> RedBlackTree<T> {
-        if color == Red {
-            if let &Node(Red, ref ll, ref lv, ref lr) = left.as_ref() {
-                if let &Node(Red, ref lrl, ref lrv, ref lrr) = lr.as_ref() {
-                    return Node(Red,
-                                Rc::new(Node(Black, ll.clone(), lv.clone(), lrl.clone())),
+        if color == Black {
+            if let &Node(Red, ref ll, ref lv, ref lr, _) = left.as_ref() {
+                if let &Node(Red, ref lrl, ref lrv, ref lrr, _) = lr.as_ref() {
+                    return node(Red,
+                                Rc::new(node(Black, ll.clone(), lv.clone(), lrl.clone())),
                                 lrv.clone(),
-                                Rc::new(Node(Black, lrr.clone(), val.clone(), right.clone())))
+                                Rc::new(node(Black, lrr.clone(), val.clone(), right.clone())))
                 }
             }
         }
-        Node(color, left.clone(), val.clone(), right.clone())
+        node(color, left.clone(), val.clone(), right.clone())
     }
 
     fn rlbalance(color: Color, left: &Rc<RedBlackTree<T>>, val: &T, right: &Rc<RedBlackTree<T>>) -> RedBlackTree<T> {
-        if color == Red {
-            if let &Node(Red, ref rl, ref rv, ref rr) = right.as_ref() {
-                if let &Node(Red, ref rll, ref rlv, ref rlr) = rl.as_ref() {
-                    return Node(Red,
-                                Rc::new(Node(Black, left.clone(), val.clone(), rll.clone())),
+        if color == Black {
+            if let &Node(Red, ref rl, ref rv, ref rr, _) = right.as_ref() {
+                if let &Node(Red, ref rll, ref rlv, ref rlr, _) = rl.as_ref() {
+                    return node(Red,
+                                Rc::new(node(Black, left.clone(), val.clone(), rll.clone())),
                                 rlv.clone(),
-                                Rc::new(Node(Black, rlr.clone(), rv.clone(), rr.clone())))
+                                Rc::new(node(Black, rlr.clone(), rv.clone(), rr.clone())))
                 }
             }
         }
-        Node(color, left.clone(), val.clone(), right.clone())
+        node(color, left.clone(), val.clone(), right.clone())
     }
 
     fn rrbalance(color: Color, left: &Rc<RedBlackTree<T>>, val: &T, right: &Rc<RedBlackTree<T>>) -> RedBlackTree<T> {
-        if color == Red {
-            if let &Node(Red, ref rl, ref rv, ref rr) = right.as_ref() {
-                if let &Node(Red, ref rrl, ref rrv, ref rrr) = rr.as_ref() {
-                    return Node(Red,
-                                Rc::new(Node(Black, left.clone(), val.clone(), rl.clone())),
+        if color == Black {
+            if let &Node(Red, ref rl, ref rv, ref rr, _) = right.as_ref() {
+                if let &Node(Red, ref rrl, ref rrv, ref rrr, _) = rr.as_ref() {
+                    return node(Red,
+                                Rc::new(node(Black, left.clone(), val.clone(), rl.clone())),
                                 rv.clone(),
-                                Rc::new(Node(Black, rrl.clone(), rrv.clone(), rrr.clone())))
+                                Rc::new(node(Black, rrl.clone(), rrv.clone(), rrr.clone())))
                 }
             }
         }
-        Node(color, left.clone(), val.clone(), right.clone())
+        node(color, left.clone(), val.clone(), right.clone())
     }
 
     pub fn singleton(x: T) -> RedBlackTree<T> {
-        Node(Black, Rc::new(Empty), x, Rc::new(Empty))
+        node(Black, Rc::new(Empty), x, Rc::new(Empty))
     }
 
     fn singleton_c(x: T, color: Color) -> RedBlackTree<T> {
-        Node(color, Rc::new(Empty), x, Rc::new(Empty))
+        node(color, Rc::new(Empty), x, Rc::new(Empty))
     }
 
     fn set_root_color(&self, color: Color) -> RedBlackTree<T> {
-        if let &Node(_, ref left, ref val, ref right) = self {
-            Node(color, left.clone(), val.clone(), right.clone())
+        if let &Node(_, ref left, ref val, ref right, _) = self {
+            node(color, left.clone(), val.clone(), right.clone())
         } else {
             Empty
         }
@@ -229,7 +245,7 @@ impl <T> RedBlackTree<T>
                             let (x, xs) = rest.to_cons();
                             TailRec::Func(box move || {
                                 trampoline(xs.clone(), n - n/2 -1, box move |rtree: RedBlackTree<T>, rcount: i32, rest: List<T>| {
-                                    k(Node(Black,
+                                    k(node(Black,
                                            Rc::new(if lcount > rcount {ltree.set_root_color(Red)} else {ltree.clone()}),
                                            x,
                                            Rc::new(rtree.clone())
@@ -262,7 +278,7 @@ impl <T> RedBlackTree<T>
                     walk_cps(xs, n/2, box move |ltree: RedBlackTree<T>, lcount: i32, rest: List<T>| {
                         let (x, xs) = rest.decom();
                         walk_cps(xs.clone(), n - n/2 -1, box move |rtree: RedBlackTree<T>, rcount: i32, rest: List<T>| {
-                            k(Node(
+                            k(node(
                                 Black,
                                 Rc::new(if lcount > rcount {ltree.set_root_color(Red)} else {ltree.clone()}),
                                 x.clone(),
@@ -291,7 +307,7 @@ impl <T> RedBlackTree<T>
                     let (ltree, lcount, rest) = walk(xs, n/2);
                     let (x, xs) = rest.to_cons();
                     let (rtree, rcount, rest) = walk(xs, n - n/2 -1);
-                    (Node(
+                    (node(
                         Black,
                         Rc::new(if lcount > rcount {ltree.set_root_color(Red)} else {ltree}),
                         x,
@@ -314,9 +330,9 @@ impl <T> RedBlackTree<T>
                 2 => {
                     let x0 = xs.head().clone();
                     let x1 = xs.tail().head().clone();
-                    (Node(
+                    (node(
                         Black,
-                        Rc::new(Node(Red, Rc::new(Empty), x0, Rc::new(Empty) )),
+                        Rc::new(node(Red, Rc::new(Empty), x0, Rc::new(Empty) )),
                         x1,
                         Rc::new(Empty)
                     ), 1)
@@ -325,16 +341,16 @@ impl <T> RedBlackTree<T>
                     let x0 = xs.head().clone();
                     let x1 = xs.tail().head().clone();
                     let x2 = xs.tail().tail().head().clone();
-                    (Node(
+                    (node(
                         Red,
-                        Rc::new(Node(
+                        Rc::new(node(
                             Black,
                             Rc::new(Empty),
                             x0,
                             Rc::new(Empty)
                         )),
                         x1,
-                        Rc::new(Node(
+                        Rc::new(node(
                             Black,
                             Rc::new(Empty),
                             x2,
@@ -349,14 +365,14 @@ impl <T> RedBlackTree<T>
                     let (ltree, lbc) = walk(heads, n/2);
                     let (rtree, rbc) = walk(tail.clone(), n - n/2 - 1);
                     if n%2 == 1 {
-                        (Node(
+                        (node(
                             Red,
                             Rc::new(ltree.set_root_color(Black)),
                             root.clone(),
                             Rc::new(rtree.set_root_color(Black))
                         ), if (n/2)%2 == 1 {lbc + 1} else {lbc})
                     } else if lbc == rbc {
-                        (Node(
+                        (node(
                             Black,
                             Rc::new(ltree.clone()),
                             root.clone(),
@@ -364,14 +380,14 @@ impl <T> RedBlackTree<T>
                         ), lbc + 1)
                     } else {
                         if (n/2)%2 == 1 {
-                            (Node(
+                            (node(
                                 Black,
                                 Rc::new(ltree.set_root_color(Black)),
                                 root.clone(),
                                 Rc::new(rtree.clone())
                             ), rbc + 1)
                         } else {
-                            (Node(
+                            (node(
                                 Black,
                                 Rc::new(ltree.clone()),
                                 root.clone(),
@@ -397,8 +413,8 @@ impl <T> RedBlackTree<T>
     pub fn insert_naive(&self, elem: T) -> Self {
         fn ins<T: Clone + Ord>(tree: &RedBlackTree<T>, elem: T) -> RedBlackTree<T> {
             match tree {
-                &Empty => Node(Red, Rc::new(Empty), elem, Rc::new(Empty)),
-                &Node(color, ref left, ref val, ref right) => {
+                &Empty => node(Red, Rc::new(Empty), elem, Rc::new(Empty)),
+                &Node(color, ref left, ref val, ref right, _) => {
                     match elem.cmp(val) {
                         Less =>  RedBlackTree::balance(color, &Rc::new(ins(left, elem)), val, right),
                         Greater => RedBlackTree::balance(color, left, val, &Rc::new(ins(right, elem))),
@@ -407,8 +423,8 @@ impl <T> RedBlackTree<T>
                 }
             }
         }
-        if let Node(_, left, val, right) = ins(self, elem) {
-            Node(Black, left, val, right)
+        if let Node(_, left, val, right, _) = ins(self, elem) {
+            node(Black, left, val, right)
         } else {
             panic!("ins result is empty.")
         }
@@ -418,13 +434,13 @@ impl <T> RedBlackTree<T>
 impl <T> RedBlackTree<T>
     where T: Clone + Ord + fmt::Debug
 {
-    fn black_count(&self) -> Result<i32, String> {
+    fn black_count(&self) -> Result<i32, InvariantError> {
         match self {
             &Empty => Ok(0),
-            &Node(color, ref left, ref val, ref right) => {
+            &Node(color, ref left, ref val, ref right, _) => {
                 let lbc = left.black_count()?;
                 let rbc = right.black_count()?;
-                if lbc != rbc { return Err(format!("Unbalanced! On node {:?}, left black count is {} but right is {}.", val, lbc, rbc)) }
+                if lbc != rbc { return Err(InvariantError::Unbalanced(format!("On node {:?}, left black count is {} but right is {}.", val, lbc, rbc))) }
                 if color == Red {
                     Ok(lbc)
                 } else {
@@ -436,20 +452,31 @@ impl <T> RedBlackTree<T>
     pub fn self_check(&self) -> Result<(), String>
         where T: ::std::fmt::Debug
     {
+        self.check_invariants().map_err(|e| e.to_string())
+    }
+}
+
+impl <T> Validate for RedBlackTree<T>
+    where T: Clone + Ord + fmt::Debug
+{
+    fn check_invariants(&self) -> Result<(), InvariantError> {
         match self {
             &Empty => Ok(()),
-            &Node(color, ref left, ref val, ref right) => {
-                if let &Node(lcolor, _, ref lval, _) = left.as_ref() {
-                    if val < lval { return Err(format!("Not btree! left: {:?} is greater than parent {:?}.", lval, val)) }
-                    if color == Red && lcolor == Red { return Err(format!("Color error! Both node {:?} and right {:?} is Red.", val, lval)) }
+            &Node(color, ref left, ref val, ref right, size) => {
+                if let &Node(lcolor, _, ref lval, _, _) = left.as_ref() {
+                    if val < lval { return Err(InvariantError::Order(format!("left: {:?} is greater than parent {:?}.", lval, val))) }
+                    if color == Red && lcolor == Red { return Err(InvariantError::Color(format!("Both node {:?} and left {:?} is Red.", val, lval))) }
+                }
+                if let &Node(rcolor, _, ref rval, _, _) = right.as_ref() {
+                    if rval < val { return Err(InvariantError::Order(format!("right: {:?} is less than parent {:?}.", rval, val))) }
+                    if color == Red && rcolor == Red { return Err(InvariantError::Color(format!("Both node {:?} and right {:?} is Red.", val, rval))) }
                 }
-                if let &Node(rcolor, _, ref rval, _) = right.as_ref() {
-                    if rval < val { return Err(format!("Not btree! right: {:?} is less than parent {:?}.", rval, val)) }
-                    if color == Red && rcolor == Red { return Err(format!("Color error! Both node {:?} and right {:?} is Red.", val, rval)) }
+                if size != left.size() + right.size() + 1 {
+                    return Err(InvariantError::Size(format!("node {:?} records {} elements but has {}.", val, size, left.size() + right.size() + 1)))
                 }
                 let _ = self.black_count()?;
-                let _ = left.self_check()?;
-                let _ = right.self_check()?;
+                let _ = left.check_invariants()?;
+                let _ = right.check_invariants()?;
                 Ok(())
             }
         }
@@ -469,7 +496,7 @@ impl <T> Set<T> for RedBlackTree<T>
     fn member(&self, elem: &T) -> bool {
         match self {
             &Empty => false,
-            &Node(_, ref left, ref val, ref right) => {
+            &Node(_, ref left, ref val, ref right, _) => {
                 match elem.cmp(val) {
                     Less => left.member(elem),
                     Greater => right.member(elem),
@@ -480,41 +507,76 @@ impl <T> Set<T> for RedBlackTree<T>
     }
 
     fn insert(&self, elem: T) -> Self {
-        fn ins<T: Clone + Ord>(tree: &RedBlackTree<T>, elem: T, d: Direction) -> RedBlackTree<T> {
-            println!("d: {:?}", d);
+        // 演習 3.10 (b) 挿入した向きも一緒に返し、親の親では
+        // 赤が続きうる側だけを調べる。Root は新しい節点か変化なし
+        fn ins<T: Clone + Ord>(tree: &RedBlackTree<T>, elem: T) -> (RedBlackTree<T>, Direction) {
             match tree {
-                &Empty => Node(Red, Rc::new(Empty), elem, Rc::new(Empty)),
-                &Node(color, ref left, ref val, ref right) => {
+                &Empty => (node(Red, Rc::new(Empty), elem, Rc::new(Empty)), Direction::Root),
+                &Node(color, ref left, ref val, ref right, _) => {
                     match elem.cmp(val) {
                         Less => {
-                            let left = ins(left, elem, Direction::Left);
-                            if d == Direction::Left {
-                                RedBlackTree::llbalance(color, &Rc::new(left), val, right)
-                            } else if d == Direction::Right {
-                                RedBlackTree::lrbalance(color, &Rc::new(left), val, right)
-                            } else {
-                                Node(color, Rc::new(left), val.clone(), right.clone())
-                            }
+                            let (left, d) = ins(left, elem);
+                            let left = Rc::new(left);
+                            let t = match d {
+                                Direction::Left => RedBlackTree::llbalance(color, &left, val, right),
+                                Direction::Right => RedBlackTree::lrbalance(color, &left, val, right),
+                                Direction::Root => node(color, left, val.clone(), right.clone())
+                            };
+                            (t, Direction::Left)
                         },
                         Greater => {
-                            let right = ins(right, elem, Direction::Right);
-                            if d == Direction::Left {
-                                RedBlackTree::lbalance(color, left, val, &Rc::new(right))
-                            } else if d == Direction::Right {
-                                RedBlackTree::rrbalance(color, left, val, &Rc::new(right))
-                            } else {
-                                Node(color, left.clone(), val.clone(), Rc::new(right))
-                            }
+                            let (right, d) = ins(right, elem);
+                            let right = Rc::new(right);
+                            let t = match d {
+                                Direction::Left => RedBlackTree::rlbalance(color, left, val, &right),
+                                Direction::Right => RedBlackTree::rrbalance(color, left, val, &right),
+                                Direction::Root => node(color, left.clone(), val.clone(), right)
+                            };
+                            (t, Direction::Right)
                         },
-                        Equal => tree.clone(),
+                        Equal => (tree.clone(), Direction::Root)
                     }
                 }
             }
         }
-        if let Node(_, left, val, right) = ins(self, elem, Direction::Root) {
-            Node(Black, left, val, right)
+        if let (Node(_, left, val, right, _), _) = ins(self, elem) {
+            node(Black, left, val, right)
         } else {
             panic!("ins result is empty.")
         }
     }
+
+    // 部分木の要素数を持っているので O(1)
+    fn len(&self) -> usize {
+        self.size()
+    }
+}
+
+// 中間順に辿るので、要素は昇順に出てくる
+impl <T> IntoIterator for RedBlackTree<T>
+    where T: Clone
+{
+    type Item = T;
+    type IntoIter = ::std::vec::IntoIter<T>;
+    fn into_iter(self) -> ::std::vec::IntoIter<T> {
+        let mut result = Vec::new();
+        let mut stack = Vec::new();
+        let mut point = &self;
+        loop {
+            match point {
+                &Node(_, ref left, _, _, _) => {
+                    stack.push(point);
+                    point = &**left;
+                },
+                &Empty => match stack.pop() {
+                    Some(&Node(_, _, ref val, ref right, _)) => {
+                        result.push(val.clone());
+                        point = &**right;
+                    },
+                    _ => break
+                }
+            }
+        }
+        result.into_iter()
+    }
 }
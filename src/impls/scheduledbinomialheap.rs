@@ -0,0 +1,210 @@
+/// 7.3 スケジュールされた二項ヒープ
+use std::fmt;
+use std::iter::FromIterator;
+
+use super::{Stack, List, Stream, Heap, OrderedHeap, Natural, SortedIter};
+use super::stream::StreamCell::*;
+use super::binominalheap::BinominalTree;
+
+pub enum Digit<T>
+    where T: Clone
+{
+    Zero,
+    One(BinominalTree<T>)
+}
+use self::Digit::*;
+
+impl <T> Clone for Digit<T>
+    where T: Clone
+{
+    fn clone(&self) -> Digit<T> {
+        match self {
+            &Zero => Zero,
+            &One(ref t) => One(t.clone())
+        }
+    }
+}
+
+// digits は階数の昇順。schedule には digits の中で未評価の Zero の後ろの部分が並ぶ
+pub struct ScheduledBinomialHeap<T>
+    where T: Clone
+{
+    digits: Stream<Digit<T>>,
+    schedule: List<Stream<Digit<T>>>
+}
+
+impl <T> Clone for ScheduledBinomialHeap<T>
+    where T: Clone
+{
+    fn clone(&self) -> ScheduledBinomialHeap<T> {
+        ScheduledBinomialHeap {
+            digits: self.digits.clone(),
+            schedule: self.schedule.clone()
+        }
+    }
+}
+
+// 繰り上がりはインクリメンタル。1 回の force で 1 桁だけ進む
+fn ins_tree<T>(t: BinominalTree<T>, ds: &Stream<Digit<T>>) -> Stream<Digit<T>>
+    where T: Ord + Clone + 'static
+{
+    let ds = ds.clone();
+    Stream::lazy(move || {
+        match ds.force() {
+            &Nil => Cons(One(t), Stream::empty()),
+            &Cons(Zero, ref ds) => Cons(One(t), ds.clone()),
+            &Cons(One(ref t2), ref ds) => Cons(Zero, ins_tree(t.link(t2), ds))
+        }
+    })
+}
+
+fn mrg<T>(ds1: &Stream<Digit<T>>, ds2: &Stream<Digit<T>>) -> Stream<Digit<T>>
+    where T: Ord + Clone + 'static
+{
+    let (ds1, ds2) = (ds1.clone(), ds2.clone());
+    Stream::lazy(move || {
+        match (ds1.force(), ds2.force()) {
+            (_, &Nil) => ds1.force().clone(),
+            (&Nil, _) => ds2.force().clone(),
+            (&Cons(Zero, ref ds1), &Cons(ref d, ref ds2)) => Cons(d.clone(), mrg(ds1, ds2)),
+            (&Cons(ref d, ref ds1), &Cons(Zero, ref ds2)) => Cons(d.clone(), mrg(ds1, ds2)),
+            (&Cons(One(ref t1), ref ds1), &Cons(One(ref t2), ref ds2)) => {
+                Cons(Zero, ins_tree(t1.link(t2), &mrg(ds1, ds2)))
+            }
+        }
+    })
+}
+
+// すべての桁を評価しておく
+fn normalize<T>(ds: Stream<Digit<T>>) -> Stream<Digit<T>>
+    where T: Clone + 'static
+{
+    for _ in ds.iter() {}
+    ds
+}
+
+// スケジュールの先頭のストリームを 1 桁進める
+fn exec<T>(schedule: &List<Stream<Digit<T>>>) -> List<Stream<Digit<T>>>
+    where T: Clone + 'static
+{
+    match schedule {
+        &List::Nil => List::Nil,
+        &List::Cons(ref rs) => {
+            let (ds, rest) = (&rs.0, &rs.1);
+            match ds.force() {
+                &Cons(Zero, ref job) => rest.snoc(job.clone()),
+                _ => rest.clone()
+            }
+        }
+    }
+}
+
+fn remove_min_tree<T>(ds: &Stream<Digit<T>>) -> (BinominalTree<T>, Stream<Digit<T>>)
+    where T: Ord + Clone + 'static
+{
+    match ds.force() {
+        &Nil => panic!("empty heap"),
+        &Cons(One(ref t), ref rest) if rest.is_empty() => (t.clone(), Stream::empty()),
+        &Cons(Zero, ref rest) => {
+            let (t, rest) = remove_min_tree(rest);
+            (t, Stream::cons(Zero, rest))
+        },
+        &Cons(One(ref t), ref rest) => {
+            let (t2, rest2) = remove_min_tree(rest);
+            if t.node() <= t2.node() {
+                (t.clone(), Stream::cons(Zero, rest.clone()))
+            } else {
+                (t2, Stream::cons(One(t.clone()), rest2))
+            }
+        }
+    }
+}
+
+impl <T> Heap<T> for ScheduledBinomialHeap<T>
+    where T: Ord + Clone + 'static
+{
+    fn empty() -> ScheduledBinomialHeap<T> {
+        ScheduledBinomialHeap {digits: Stream::empty(), schedule: List::Nil}
+    }
+
+    fn is_empty(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    // 1 回の insert でスケジュールを 2 つ進めるので、最悪 O(1)
+    fn insert(&self, x: T) -> ScheduledBinomialHeap<T> {
+        let digits = ins_tree(BinominalTree::singleton(x), &self.digits);
+        let schedule = exec(&exec(&self.schedule.snoc(digits.clone())));
+        ScheduledBinomialHeap {digits, schedule}
+    }
+
+    fn merge(&self, other: &ScheduledBinomialHeap<T>) -> ScheduledBinomialHeap<T> {
+        let digits = normalize(mrg(&self.digits, &other.digits));
+        ScheduledBinomialHeap {digits, schedule: List::Nil}
+    }
+
+    fn find_min(&self) -> &T {
+        self.digits.iter()
+            .filter_map(|d| match d {
+                &Zero => None,
+                &One(ref t) => Some(t.node())
+            })
+            .min()
+            .expect("empty heap")
+    }
+
+    fn delete_min(&self) -> ScheduledBinomialHeap<T> {
+        let (t, rest) = remove_min_tree(&self.digits);
+        // 子は階数の降順なので、前から積むと昇順のストリームになる
+        let children = t.sub().iter().fold(Stream::empty(), |ds, c| Stream::cons(One(c.clone()), ds));
+        let digits = normalize(mrg(&children, &rest));
+        ScheduledBinomialHeap {digits, schedule: List::Nil}
+    }
+
+    // insert が O(1) なので、順に insert するだけで O(n) になる
+    fn from_iter<I>(iter: I) -> ScheduledBinomialHeap<T>
+        where I: IntoIterator<Item = T>
+    {
+        iter.into_iter().fold(Heap::empty(), |h: ScheduledBinomialHeap<T>, x| h.insert(x))
+    }
+}
+
+impl <T> OrderedHeap<T> for ScheduledBinomialHeap<T>
+    where T: Ord + Clone + 'static
+{
+    type Order = Natural;
+}
+
+impl <T> FromIterator<T> for ScheduledBinomialHeap<T>
+    where T: Ord + Clone + 'static
+{
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> ScheduledBinomialHeap<T> {
+        <ScheduledBinomialHeap<T> as Heap<T>>::from_iter(iter)
+    }
+}
+
+impl <T> IntoIterator for ScheduledBinomialHeap<T>
+    where T: Ord + Clone + 'static
+{
+    type Item = T;
+    type IntoIter = SortedIter<ScheduledBinomialHeap<T>, T>;
+    fn into_iter(self) -> SortedIter<ScheduledBinomialHeap<T>, T> {
+        self.into_sorted_iter()
+    }
+}
+
+impl <T> fmt::Debug for ScheduledBinomialHeap<T>
+    where T: fmt::Debug + Clone + 'static
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, d) in self.digits.iter().enumerate() {
+            if i != 0 { write!(f, ", ")? }
+            match d {
+                &Zero => write!(f, "0")?,
+                &One(ref t) => write!(f, "{}:{:?}", t.rank(), t.node())?
+            }
+        }
+        write!(f, "]")
+    }
+}
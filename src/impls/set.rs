@@ -2,4 +2,11 @@ pub trait Set<T> {
     fn empty() -> Self;
     fn member(&self, elem: &T) -> bool;
     fn insert(&self, elem: T) -> Self;
+
+    // 要素を 1 つずつ辿って数えるので O(n)。要素数を持っている実装は上書きする
+    fn len(&self) -> usize
+        where Self: Clone + IntoIterator<Item = T>
+    {
+        self.clone().into_iter().count()
+    }
 }
@@ -0,0 +1,236 @@
+/// 9.3.2 歪二項ヒープ
+use std::fmt;
+use std::iter::FromIterator;
+
+use super::{Stack, List, Heap, OrderedHeap, Natural, SortedIter};
+use super::List::*;
+
+// 階数 r の歪二項木は、階数 r の二項木に高々 r 個の要素を足したもの。
+// elems はその足された要素
+pub struct SkewBinomialTree<T> {
+    rank: usize,
+    node: T,
+    elems: List<T>,
+    sub: List<SkewBinomialTree<T>>
+}
+
+impl <T> Clone for SkewBinomialTree<T>
+    where T: Clone
+{
+    fn clone(&self) -> SkewBinomialTree<T> {
+        SkewBinomialTree {
+            rank: self.rank,
+            node: self.node.clone(),
+            elems: self.elems.clone(),
+            sub: self.sub.clone()
+        }
+    }
+}
+
+impl <T> SkewBinomialTree<T>
+    where T: Ord + Clone
+{
+    fn link(&self, that: &SkewBinomialTree<T>) -> SkewBinomialTree<T> {
+        if self.node <= that.node {
+            SkewBinomialTree {
+                rank: self.rank + 1,
+                node: self.node.clone(),
+                elems: self.elems.clone(),
+                sub: self.sub.snoc(that.clone())
+            }
+        } else {
+            SkewBinomialTree {
+                rank: self.rank + 1,
+                node: that.node.clone(),
+                elems: that.elems.clone(),
+                sub: that.sub.snoc(self.clone())
+            }
+        }
+    }
+
+    fn skew_link(x: T, t1: &SkewBinomialTree<T>, t2: &SkewBinomialTree<T>) -> SkewBinomialTree<T> {
+        let SkewBinomialTree {rank, node, elems, sub} = t1.link(t2);
+        if x <= node {
+            SkewBinomialTree {rank, node: x, elems: elems.snoc(node), sub}
+        } else {
+            SkewBinomialTree {rank, node, elems: elems.snoc(x), sub}
+        }
+    }
+}
+
+// 木のリストは階数の昇順。ただし先頭の 2 つだけは同じ階数のことがある
+pub struct SkewBinomialHeap<T>(List<SkewBinomialTree<T>>);
+
+impl <T> SkewBinomialHeap<T> {
+    // 他から共有されていない木から要素を取り出して out に積む。
+    // 順序を使わないので、BootstrappedHeap の drop から呼べる
+    pub fn take_unshared(&mut self, out: &mut Vec<T>) {
+        let mut trees = Vec::new();
+        self.0.take_unshared(&mut trees);
+        while let Some(SkewBinomialTree {node, mut elems, mut sub, ..}) = trees.pop() {
+            out.push(node);
+            elems.take_unshared(out);
+            sub.take_unshared(&mut trees);
+        }
+    }
+}
+
+impl <T> Clone for SkewBinomialHeap<T>
+    where T: Clone
+{
+    fn clone(&self) -> SkewBinomialHeap<T> {
+        SkewBinomialHeap(self.0.clone())
+    }
+}
+
+fn ins_tree<T>(t: SkewBinomialTree<T>, ts: &List<SkewBinomialTree<T>>) -> List<SkewBinomialTree<T>>
+    where T: Ord + Clone
+{
+    match ts {
+        &Nil => List::singleton(t),
+        &Cons(ref rts) => {
+            let (t2, ts2) = (&rts.0, &rts.1);
+            if t.rank < t2.rank {
+                ts.snoc(t)
+            } else {
+                ins_tree(t.link(t2), ts2)
+            }
+        }
+    }
+}
+
+fn merge_trees<T>(ts1: &List<SkewBinomialTree<T>>, ts2: &List<SkewBinomialTree<T>>) -> List<SkewBinomialTree<T>>
+    where T: Ord + Clone
+{
+    match (ts1, ts2) {
+        (ts, &Nil) => ts.clone(),
+        (&Nil, ts) => ts.clone(),
+        (&Cons(ref rts1), &Cons(ref rts2)) => {
+            let (t1, ts1_) = (&rts1.0, &rts1.1);
+            let (t2, ts2_) = (&rts2.0, &rts2.1);
+            if t1.rank < t2.rank {
+                merge_trees(ts1_, ts2).snoc(t1.clone())
+            } else if t1.rank > t2.rank {
+                merge_trees(ts1, ts2_).snoc(t2.clone())
+            } else {
+                ins_tree(t1.link(t2), &merge_trees(ts1_, ts2_))
+            }
+        }
+    }
+}
+
+// 先頭の重複した階数を解消する
+fn normalize<T>(ts: &List<SkewBinomialTree<T>>) -> List<SkewBinomialTree<T>>
+    where T: Ord + Clone
+{
+    match ts {
+        &Nil => Nil,
+        &Cons(ref rts) => ins_tree(rts.0.clone(), &rts.1)
+    }
+}
+
+fn remove_min_tree<T>(ts: &List<SkewBinomialTree<T>>) -> (&SkewBinomialTree<T>, List<SkewBinomialTree<T>>)
+    where T: Ord + Clone
+{
+    let (x, xs) = ts.decom();
+    if Stack::is_empty(xs) {
+        (x, Nil)
+    } else {
+        let (y, ys) = remove_min_tree(xs);
+        if x.node <= y.node {
+            (x, xs.clone())
+        } else {
+            (y, ys.snoc(x.clone()))
+        }
+    }
+}
+
+impl <T> Heap<T> for SkewBinomialHeap<T>
+    where T: Ord + Clone
+{
+    fn empty() -> SkewBinomialHeap<T> {
+        SkewBinomialHeap(Nil)
+    }
+
+    fn is_empty(&self) -> bool {
+        Stack::is_empty(&self.0)
+    }
+
+    // 先頭の 2 つの木の階数が等しければ、x と一緒に歪リンクするだけなので最悪 O(1)
+    fn insert(&self, x: T) -> SkewBinomialHeap<T> {
+        if let &Cons(ref rts) = &self.0 {
+            if let &Cons(ref rts2) = &rts.1 {
+                let (t1, t2, rest) = (&rts.0, &rts2.0, &rts2.1);
+                if t1.rank == t2.rank {
+                    return SkewBinomialHeap(rest.snoc(SkewBinomialTree::skew_link(x, t1, t2)))
+                }
+            }
+        }
+        SkewBinomialHeap(self.0.snoc(SkewBinomialTree {rank: 0, node: x, elems: Nil, sub: Nil}))
+    }
+
+    fn merge(&self, other: &SkewBinomialHeap<T>) -> SkewBinomialHeap<T> {
+        SkewBinomialHeap(merge_trees(&normalize(&self.0), &normalize(&other.0)))
+    }
+
+    fn find_min(&self) -> &T {
+        if Stack::is_empty(&self.0) {
+            panic!("empty heap")
+        }
+        self.0.iter().map(|t| &t.node).min().unwrap()
+    }
+
+    // 取り除いた木の子を merge してから、elems の要素を 1 つずつ insert し直す
+    fn delete_min(&self) -> SkewBinomialHeap<T> {
+        if Stack::is_empty(&self.0) {
+            panic!("empty heap")
+        }
+        let (t, ts) = remove_min_tree(&self.0);
+        let merged = SkewBinomialHeap(merge_trees(&t.sub.reverse(), &normalize(&ts)));
+        t.elems.iter().fold(merged, |h, x| h.insert(x.clone()))
+    }
+
+    // insert が O(1) なので、順に insert するだけで O(n) になる
+    fn from_iter<I>(iter: I) -> SkewBinomialHeap<T>
+        where I: IntoIterator<Item = T>
+    {
+        iter.into_iter().fold(Heap::empty(), |h: SkewBinomialHeap<T>, x| h.insert(x))
+    }
+}
+
+impl <T> OrderedHeap<T> for SkewBinomialHeap<T>
+    where T: Ord + Clone
+{
+    type Order = Natural;
+}
+
+impl <T> FromIterator<T> for SkewBinomialHeap<T>
+    where T: Ord + Clone
+{
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> SkewBinomialHeap<T> {
+        <SkewBinomialHeap<T> as Heap<T>>::from_iter(iter)
+    }
+}
+
+impl <T> IntoIterator for SkewBinomialHeap<T>
+    where T: Ord + Clone
+{
+    type Item = T;
+    type IntoIter = SortedIter<SkewBinomialHeap<T>, T>;
+    fn into_iter(self) -> SortedIter<SkewBinomialHeap<T>, T> {
+        self.into_sorted_iter()
+    }
+}
+
+impl <T> fmt::Debug for SkewBinomialHeap<T>
+    where T: fmt::Debug + Clone
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, t) in self.0.iter().enumerate() {
+            if i != 0 { write!(f, ", ")? }
+            write!(f, "{}:{:?}{:?}", t.rank, t.node, t.elems)?;
+        }
+        write!(f, "]")
+    }
+}
@@ -0,0 +1,152 @@
+/// スキューヒープ
+use std::rc::Rc;
+use std::mem;
+use std::fmt;
+use std::iter::FromIterator;
+
+use super::{Heap, OrderedHeap, Natural, SortedIter};
+
+// 左偏ヒープから階数を取り除き、merge のたびに必ず左右を入れ替える。
+// 計算量は償却 O(log n)
+#[derive(Clone)]
+pub enum SkewHeap<T> {
+    Empty,
+    Tree(Rc<(T, SkewHeap<T>, SkewHeap<T>)>)
+}
+use self::SkewHeap::*;
+
+impl <T> Heap<T> for SkewHeap<T>
+    where T: Ord + Clone
+{
+    fn empty() -> SkewHeap<T> {
+        Empty
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            &Empty => true,
+            &Tree(_) => false
+        }
+    }
+
+    fn insert(&self, x: T) -> SkewHeap<T> {
+        Tree(Rc::new((x, Empty, Empty))).merge(self)
+    }
+
+    // 右の経路は一時的に長くなることがあるので、再帰せずに経路を積んでから組み立てる
+    fn merge(&self, other: &SkewHeap<T>) -> SkewHeap<T> {
+        let mut path = Vec::new();
+        let (mut h1, mut h2) = (self, other);
+        let rest = loop {
+            match (h1, h2) {
+                (h, &Empty) => break h.clone(),
+                (&Empty, h) => break h.clone(),
+                (&Tree(ref t1), &Tree(ref t2)) => {
+                    if t1.0 <= t2.0 {
+                        path.push((&t1.0, &t1.1));
+                        h1 = &t1.2;
+                    } else {
+                        path.push((&t2.0, &t2.1));
+                        h2 = &t2.2;
+                    }
+                }
+            }
+        };
+        path.into_iter().rev().fold(rest, |h, (x, a)| Tree(Rc::new((x.clone(), h, a.clone()))))
+    }
+
+    fn find_min(&self) -> &T {
+        match self {
+            &Empty => panic!("empty heap"),
+            &Tree(ref t) => &t.0
+        }
+    }
+
+    fn delete_min(&self) -> SkewHeap<T> {
+        match self {
+            &Empty => panic!("empty heap"),
+            &Tree(ref t) => t.1.merge(&t.2)
+        }
+    }
+}
+
+impl <T> OrderedHeap<T> for SkewHeap<T>
+    where T: Ord + Clone
+{
+    type Order = Natural;
+}
+
+// 他から共有されていない節点なら、子を取り外して stack に積む
+fn take_children<T>(h: &mut SkewHeap<T>, stack: &mut Vec<SkewHeap<T>>) {
+    if let &mut Tree(ref mut t) = h {
+        if let Some(node) = Rc::get_mut(t) {
+            if let Tree(_) = node.1 {
+                stack.push(mem::replace(&mut node.1, Empty));
+            }
+            if let Tree(_) = node.2 {
+                stack.push(mem::replace(&mut node.2, Empty));
+            }
+        }
+    }
+}
+
+// insert は小さい要素を根にして元のヒープを左の子にするので、降順の入力では
+// 左の枝が n 段になる。既定の drop はその深さだけ再帰するので、子を取り外してから落とす
+impl <T> Drop for SkewHeap<T> {
+    fn drop(&mut self) {
+        let mut stack = Vec::new();
+        take_children(self, &mut stack);
+        while let Some(mut h) = stack.pop() {
+            take_children(&mut h, &mut stack);
+        }
+    }
+}
+
+impl <T> FromIterator<T> for SkewHeap<T>
+    where T: Ord + Clone
+{
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> SkewHeap<T> {
+        <SkewHeap<T> as Heap<T>>::from_iter(iter)
+    }
+}
+
+impl <T> IntoIterator for SkewHeap<T>
+    where T: Ord + Clone
+{
+    type Item = T;
+    type IntoIter = SortedIter<SkewHeap<T>, T>;
+    fn into_iter(self) -> SortedIter<SkewHeap<T>, T> {
+        self.into_sorted_iter()
+    }
+}
+
+impl <T> fmt::Debug for SkewHeap<T>
+    where T: fmt::Debug
+{
+    // 左の枝が深い木でも溢れないように、書き出すものを stack に積んで辿る
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        enum Item<'a, T: 'a> {
+            Heap(&'a SkewHeap<T>),
+            Elem(&'a T),
+            Text(&'static str)
+        }
+        let mut stack = vec![Item::Heap(self)];
+        while let Some(item) = stack.pop() {
+            match item {
+                Item::Heap(&Empty) => write!(f, "E")?,
+                Item::Heap(&Tree(ref t)) => {
+                    write!(f, "T(")?;
+                    stack.push(Item::Text(")"));
+                    stack.push(Item::Heap(&t.2));
+                    stack.push(Item::Text(", "));
+                    stack.push(Item::Heap(&t.1));
+                    stack.push(Item::Text(", "));
+                    stack.push(Item::Elem(&t.0));
+                },
+                Item::Elem(x) => write!(f, "{:?}", x)?,
+                Item::Text(s) => write!(f, "{}", s)?
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,395 @@
+/// 5.4 スプレーヒープ
+use std::rc::Rc;
+use std::mem;
+use std::fmt;
+use std::iter::FromIterator;
+
+use super::{Heap, OrderedHeap, Natural, SortedIter};
+
+// 左の部分木の要素は根以下、右の部分木の要素は根以上
+#[derive(Clone)]
+pub enum SplayHeap<T> {
+    Empty,
+    Tree(Rc<(SplayHeap<T>, T, SplayHeap<T>)>)
+}
+use self::SplayHeap::*;
+
+fn tree<T>(a: SplayHeap<T>, x: T, b: SplayHeap<T>) -> SplayHeap<T> {
+    Tree(Rc::new((a, x, b)))
+}
+
+impl <T> SplayHeap<T>
+    where T: Ord + Clone
+{
+    // pivot 以下の要素からなる木と、pivot より大きい要素からなる木に分ける。
+    // 途中で辿った 2 つの節点ごとに回転するので、辿った経路の深さはおよそ半分になる。
+    // 整列済みの入力から作った木では経路が n 段になるので、再帰せずに
+    // 各段で small と big をどう包むかを path に積んでおき、下から組み立てる
+    pub fn partition(&self, pivot: &T) -> (SplayHeap<T>, SplayHeap<T>) {
+        // small は tree(l, x, small) に、big は tree(big, y, r) に包む
+        let mut path = Vec::new();
+        let mut point = self;
+        let mut small;
+        let mut big;
+        loop {
+            match point {
+                &Empty => {
+                    small = Empty;
+                    big = Empty;
+                    break
+                },
+                &Tree(ref t) => {
+                    let (ref a, ref x, ref b) = **t;
+                    if x <= pivot {
+                        match b {
+                            &Empty => {
+                                small = point.clone();
+                                big = Empty;
+                                break
+                            },
+                            &Tree(ref u) => {
+                                let (ref b1, ref y, ref b2) = **u;
+                                if y <= pivot {
+                                    path.push((Some((tree(a.clone(), x.clone(), b1.clone()), y.clone())), None));
+                                    point = b2;
+                                } else {
+                                    path.push((Some((a.clone(), x.clone())), Some((y.clone(), b2.clone()))));
+                                    point = b1;
+                                }
+                            }
+                        }
+                    } else {
+                        match a {
+                            &Empty => {
+                                small = Empty;
+                                big = point.clone();
+                                break
+                            },
+                            &Tree(ref u) => {
+                                let (ref a1, ref y, ref a2) = **u;
+                                if y <= pivot {
+                                    path.push((Some((a1.clone(), y.clone())), Some((x.clone(), b.clone()))));
+                                    point = a2;
+                                } else {
+                                    path.push((None, Some((y.clone(), tree(a2.clone(), x.clone(), b.clone())))));
+                                    point = a1;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        while let Some((s, b)) = path.pop() {
+            if let Some((l, x)) = s {
+                small = tree(l, x, small);
+            }
+            if let Some((y, r)) = b {
+                big = tree(big, y, r);
+            }
+        }
+        (small, big)
+    }
+
+    // 演習 5.4 pivot 以下の要素だけを残す
+    pub fn smaller(&self, pivot: &T) -> SplayHeap<T> {
+        match self {
+            &Empty => Empty,
+            &Tree(ref t) => {
+                let (ref a, ref x, ref b) = **t;
+                if x > pivot {
+                    match a {
+                        &Empty => Empty,
+                        &Tree(ref u) => {
+                            let (ref a1, ref y, ref a2) = **u;
+                            if y > pivot {
+                                a1.smaller(pivot)
+                            } else {
+                                tree(a1.clone(), y.clone(), a2.smaller(pivot))
+                            }
+                        }
+                    }
+                } else {
+                    match b {
+                        &Empty => self.clone(),
+                        &Tree(ref u) => {
+                            let (ref b1, ref y, ref b2) = **u;
+                            if y <= pivot {
+                                tree(tree(a.clone(), x.clone(), b1.clone()), y.clone(), b2.smaller(pivot))
+                            } else {
+                                tree(a.clone(), x.clone(), b1.smaller(pivot))
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // 演習 5.4 pivot より大きい要素だけを残す
+    pub fn bigger(&self, pivot: &T) -> SplayHeap<T> {
+        match self {
+            &Empty => Empty,
+            &Tree(ref t) => {
+                let (ref a, ref x, ref b) = **t;
+                if x <= pivot {
+                    match b {
+                        &Empty => Empty,
+                        &Tree(ref u) => {
+                            let (ref b1, ref y, ref b2) = **u;
+                            if y <= pivot {
+                                b2.bigger(pivot)
+                            } else {
+                                tree(b1.bigger(pivot), y.clone(), b2.clone())
+                            }
+                        }
+                    }
+                } else {
+                    match a {
+                        &Empty => self.clone(),
+                        &Tree(ref u) => {
+                            let (ref a1, ref y, ref a2) = **u;
+                            if y <= pivot {
+                                tree(a2.bigger(pivot), x.clone(), b.clone())
+                            } else {
+                                tree(a1.bigger(pivot), y.clone(), tree(a2.clone(), x.clone(), b.clone()))
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // 演習 5.7 整列済みの列から平衡した木を O(n) で作る
+    pub fn from_sorted(xs: &[T]) -> SplayHeap<T> {
+        if xs.is_empty() {
+            return Empty
+        }
+        let mid = xs.len() / 2;
+        tree(SplayHeap::from_sorted(&xs[..mid]), xs[mid].clone(), SplayHeap::from_sorted(&xs[mid + 1..]))
+    }
+
+    // 中間順に辿って整列済みの Vec にする
+    pub fn to_sorted_vec(&self) -> Vec<T> {
+        let mut result = Vec::new();
+        let mut stack = Vec::new();
+        let mut point = self;
+        loop {
+            match point {
+                &Tree(ref t) => {
+                    stack.push(t);
+                    point = &t.0;
+                },
+                &Empty => match stack.pop() {
+                    Some(t) => {
+                        result.push(t.1.clone());
+                        point = &t.2;
+                    },
+                    None => return result
+                }
+            }
+        }
+    }
+}
+
+// 演習 5.7 スプレーヒープに insert してから中間順に辿る。
+// 整列済みに近い入力では insert がほぼ O(1) で済む
+pub fn sort<T>(xs: &[T]) -> Vec<T>
+    where T: Ord + Clone
+{
+    xs.iter().fold(SplayHeap::Empty, |h, x| h.insert(x.clone())).to_sorted_vec()
+}
+
+impl <T> Heap<T> for SplayHeap<T>
+    where T: Ord + Clone
+{
+    fn empty() -> SplayHeap<T> {
+        Empty
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            &Empty => true,
+            &Tree(_) => false
+        }
+    }
+
+    fn insert(&self, x: T) -> SplayHeap<T> {
+        let (a, b) = self.partition(&x);
+        tree(a, x, b)
+    }
+
+    // 左右の部分木をそれぞれ merge する再帰は木の高さだけ深くなるので、
+    // 残りの仕事を tasks に積み、できあがった木を results に積む
+    fn merge(&self, other: &SplayHeap<T>) -> SplayHeap<T> {
+        enum Task<T> {
+            Merge(SplayHeap<T>, SplayHeap<T>),
+            Build(T)
+        }
+        let mut tasks = vec![Task::Merge(self.clone(), other.clone())];
+        let mut results = Vec::new();
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Merge(h1, h2) => match h1 {
+                    Empty => results.push(h2),
+                    Tree(ref t) => {
+                        let (ref a, ref x, ref b) = **t;
+                        let (ta, tb) = h2.partition(x);
+                        tasks.push(Task::Build(x.clone()));
+                        tasks.push(Task::Merge(tb, b.clone()));
+                        tasks.push(Task::Merge(ta, a.clone()));
+                    }
+                },
+                Task::Build(x) => {
+                    let b = results.pop().unwrap();
+                    let a = results.pop().unwrap();
+                    results.push(tree(a, x, b));
+                }
+            }
+        }
+        results.pop().unwrap()
+    }
+
+    // 整列済みの入力では左の枝が n 段になるので、再帰せずに辿る
+    fn find_min(&self) -> &T {
+        let mut t = match self {
+            &Empty => panic!("empty heap"),
+            &Tree(ref t) => t
+        };
+        loop {
+            match t.0 {
+                Empty => return &t.1,
+                Tree(ref u) => t = u
+            }
+        }
+    }
+
+    // 左の枝を 2 段ずつ辿りながら回転する。find_min と同じく再帰せずに辿り、
+    // 辿った節点を stack に積んでおいて下から組み立て直す
+    fn delete_min(&self) -> SplayHeap<T> {
+        let mut stack = Vec::new();
+        let mut point = self;
+        let mut result;
+        loop {
+            let t = match point {
+                &Empty => panic!("empty heap"),
+                &Tree(ref t) => t
+            };
+            let (ref a, ref y, ref c) = **t;
+            match a {
+                &Empty => {
+                    result = c.clone();
+                    break
+                },
+                &Tree(ref u) => {
+                    let (ref a1, ref x, ref b) = **u;
+                    match a1 {
+                        &Empty => {
+                            result = tree(b.clone(), y.clone(), c.clone());
+                            break
+                        },
+                        _ => {
+                            stack.push((x.clone(), tree(b.clone(), y.clone(), c.clone())));
+                            point = a1;
+                        }
+                    }
+                }
+            }
+        }
+        while let Some((x, r)) = stack.pop() {
+            result = tree(result, x, r);
+        }
+        result
+    }
+
+    // merge は partition を繰り返して高くつくので、順に insert する。
+    // 整列済みの入力なら insert がそれぞれ O(1) で済む
+    fn from_iter<I>(iter: I) -> SplayHeap<T>
+        where I: IntoIterator<Item = T>
+    {
+        iter.into_iter().fold(Heap::empty(), |h: SplayHeap<T>, x| h.insert(x))
+    }
+}
+
+impl <T> OrderedHeap<T> for SplayHeap<T>
+    where T: Ord + Clone
+{
+    type Order = Natural;
+}
+
+// 他から共有されていない節点なら、子を取り外して stack に積む
+fn take_children<T>(h: &mut SplayHeap<T>, stack: &mut Vec<SplayHeap<T>>) {
+    if let &mut Tree(ref mut t) = h {
+        if let Some(node) = Rc::get_mut(t) {
+            if let Tree(_) = node.0 {
+                stack.push(mem::replace(&mut node.0, Empty));
+            }
+            if let Tree(_) = node.2 {
+                stack.push(mem::replace(&mut node.2, Empty));
+            }
+        }
+    }
+}
+
+// 既定の drop は左の枝の深さだけ再帰するので、整列済みの入力から作った木で
+// スタックを使い果たす。子を取り外してから落とせば、それぞれの drop は 1 段で済む
+impl <T> Drop for SplayHeap<T> {
+    fn drop(&mut self) {
+        let mut stack = Vec::new();
+        take_children(self, &mut stack);
+        while let Some(mut h) = stack.pop() {
+            take_children(&mut h, &mut stack);
+        }
+    }
+}
+
+impl <T> FromIterator<T> for SplayHeap<T>
+    where T: Ord + Clone
+{
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> SplayHeap<T> {
+        <SplayHeap<T> as Heap<T>>::from_iter(iter)
+    }
+}
+
+impl <T> IntoIterator for SplayHeap<T>
+    where T: Ord + Clone
+{
+    type Item = T;
+    type IntoIter = SortedIter<SplayHeap<T>, T>;
+    fn into_iter(self) -> SortedIter<SplayHeap<T>, T> {
+        self.into_sorted_iter()
+    }
+}
+
+impl <T> fmt::Debug for SplayHeap<T>
+    where T: fmt::Debug
+{
+    // 左の枝が深い木でも溢れないように、書き出すものを stack に積んで辿る
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        enum Item<'a, T: 'a> {
+            Heap(&'a SplayHeap<T>),
+            Elem(&'a T),
+            Text(&'static str)
+        }
+        let mut stack = vec![Item::Heap(self)];
+        while let Some(item) = stack.pop() {
+            match item {
+                Item::Heap(&Empty) => write!(f, "E")?,
+                Item::Heap(&Tree(ref t)) => {
+                    let (ref a, ref x, ref b) = **t;
+                    write!(f, "T(")?;
+                    stack.push(Item::Text(")"));
+                    stack.push(Item::Heap(b));
+                    stack.push(Item::Text(", "));
+                    stack.push(Item::Elem(x));
+                    stack.push(Item::Text(", "));
+                    stack.push(Item::Heap(a));
+                },
+                Item::Elem(x) => write!(f, "{:?}", x)?,
+                Item::Text(s) => write!(f, "{}", s)?
+            }
+        }
+        Ok(())
+    }
+}
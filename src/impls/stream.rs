@@ -0,0 +1,298 @@
+/// 4 遅延評価とストリーム
+use std::rc::Rc;
+use std::mem;
+use std::cell::{Cell, UnsafeCell};
+use std::boxed::FnBox;
+use std::fmt;
+
+use super::{Stack, List};
+
+// 実際に評価されたサスペンションの数。最悪計算量の確認に使う
+thread_local!(static FORCE_COUNT: Cell<usize> = Cell::new(0));
+
+pub fn force_count() -> usize {
+    FORCE_COUNT.with(|c| c.get())
+}
+
+pub fn reset_force_count() {
+    FORCE_COUNT.with(|c| c.set(0))
+}
+
+// $e に相当する。最初に force されたときにだけ計算し、結果をメモ化する。
+// Susp の Clone は参照カウントを増やすだけなので、複製同士でメモ化された値を共有する。
+pub struct Susp<T> {
+    inner: Rc<SuspInner<T>>
+}
+
+struct SuspInner<T> {
+    value: UnsafeCell<Option<T>>,
+    thunk: Cell<Option<Box<FnBox() -> T>>>
+}
+
+impl <T> Clone for Susp<T> {
+    fn clone(&self) -> Susp<T> {
+        Susp {inner: self.inner.clone()}
+    }
+}
+
+impl <T> Susp<T> {
+    pub fn new<F>(f: F) -> Susp<T>
+        where F: FnOnce() -> T + 'static
+    {
+        Susp {
+            inner: Rc::new(SuspInner {
+                value: UnsafeCell::new(None),
+                thunk: Cell::new(Some(Box::new(f)))
+            })
+        }
+    }
+
+    // 評価済みの値から作る
+    pub fn value(x: T) -> Susp<T> {
+        Susp {
+            inner: Rc::new(SuspInner {
+                value: UnsafeCell::new(Some(x)),
+                thunk: Cell::new(None)
+            })
+        }
+    }
+
+    pub fn is_forced(&self) -> bool {
+        unsafe { (*self.inner.value.get()).is_some() }
+    }
+
+    pub fn force(&self) -> &T {
+        // value は一度 Some になったら二度と書き換えないので、
+        // 返した参照は Susp が生きている限り有効
+        unsafe {
+            let value = self.inner.value.get();
+            if (*value).is_none() {
+                let thunk = self.inner.thunk.take().expect("suspension forced recursively");
+                FORCE_COUNT.with(|c| c.set(c.get() + 1));
+                let x = thunk();
+                *value = Some(x);
+            }
+            (*value).as_ref().unwrap()
+        }
+    }
+
+    // 他から共有されていない評価済みのサスペンションなら、値を取り出す。
+    // 深い構造を drop するときに、中身を再帰せずに取り外すのに使う
+    pub fn take(&mut self) -> Option<T> {
+        match Rc::get_mut(&mut self.inner) {
+            Some(inner) => unsafe { (*inner.value.get()).take() },
+            None => None
+        }
+    }
+}
+
+impl <T> fmt::Debug for Susp<T>
+    where T: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_forced() {
+            write!(f, "$({:?})", self.force())
+        } else {
+            write!(f, "$(..)")
+        }
+    }
+}
+
+pub enum StreamCell<T> {
+    Nil,
+    Cons(T, Stream<T>)
+}
+use self::StreamCell::*;
+
+impl <T> Clone for StreamCell<T>
+    where T: Clone
+{
+    fn clone(&self) -> StreamCell<T> {
+        match self {
+            &Nil => Nil,
+            &Cons(ref x, ref s) => Cons(x.clone(), s.clone())
+        }
+    }
+}
+
+pub struct Stream<T>(Susp<StreamCell<T>>);
+
+impl <T> Clone for Stream<T> {
+    fn clone(&self) -> Stream<T> {
+        Stream(self.0.clone())
+    }
+}
+
+impl <T> Stream<T> {
+    // 唯一の所有者で評価済みの Cons なら、後ろのストリームを取り外して返す
+    fn take_tail(&mut self) -> Option<Stream<T>> {
+        let inner = match Rc::get_mut(&mut (self.0).inner) {
+            Some(inner) => inner,
+            None => return None
+        };
+        match unsafe { &mut *inner.value.get() } {
+            &mut Some(Cons(_, ref mut s)) => Some(mem::replace(s, Stream(Susp::value(Nil)))),
+            _ => None
+        }
+    }
+}
+
+// 再帰的に drop すると長いストリームでスタックが溢れるので、セルを 1 つずつ切り離す
+impl <T> Drop for Stream<T> {
+    fn drop(&mut self) {
+        let mut next = match self.take_tail() {
+            Some(s) => s,
+            None => return
+        };
+        loop {
+            next = match next.take_tail() {
+                Some(s) => s,
+                None => return
+            };
+        }
+    }
+}
+
+impl <T> Stream<T>
+    where T: Clone + 'static
+{
+    pub fn empty() -> Stream<T> {
+        Stream(Susp::value(Nil))
+    }
+
+    pub fn cons(x: T, s: Stream<T>) -> Stream<T> {
+        Stream(Susp::value(Cons(x, s)))
+    }
+
+    pub fn lazy<F>(f: F) -> Stream<T>
+        where F: FnOnce() -> StreamCell<T> + 'static
+    {
+        Stream(Susp::new(f))
+    }
+
+    pub fn from_list(xs: &List<T>) -> Stream<T> {
+        xs.reverse().iter().fold(Stream::empty(), |s, x| {
+            Stream::cons(x.clone(), s)
+        })
+    }
+
+    pub fn force(&self) -> &StreamCell<T> {
+        self.0.force()
+    }
+
+    pub fn is_forced(&self) -> bool {
+        self.0.is_forced()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self.force() {
+            &Nil => true,
+            &Cons(_, _) => false
+        }
+    }
+
+    pub fn head(&self) -> &T {
+        match self.force() {
+            &Nil => panic!("nil head"),
+            &Cons(ref x, _) => x
+        }
+    }
+
+    pub fn tail(&self) -> &Stream<T> {
+        match self.force() {
+            &Nil => panic!("nil tail"),
+            &Cons(_, ref s) => s
+        }
+    }
+
+    pub fn iter(&self) -> Iter<T> {
+        Iter {point: self}
+    }
+
+    pub fn to_list(&self) -> List<T> {
+        self.iter().fold(List::empty(), |xs, x| xs.snoc(x.clone())).reverse()
+    }
+
+    // ++ はインクリメンタル
+    pub fn append(&self, other: &Stream<T>) -> Stream<T> {
+        let s = self.clone();
+        let t = other.clone();
+        Stream::lazy(move || {
+            match s.force() {
+                &Nil => t.force().clone(),
+                &Cons(ref x, ref s) => Cons(x.clone(), s.append(&t))
+            }
+        })
+    }
+
+    // take はインクリメンタル
+    pub fn take(&self, n: usize) -> Stream<T> {
+        if n == 0 {
+            return Stream::empty()
+        }
+        let s = self.clone();
+        Stream::lazy(move || {
+            match s.force() {
+                &Nil => Nil,
+                &Cons(ref x, ref s) => Cons(x.clone(), s.take(n - 1))
+            }
+        })
+    }
+
+    // drop はモノリシック
+    pub fn drop(&self, n: usize) -> Stream<T> {
+        let s = self.clone();
+        Stream::lazy(move || {
+            let mut point = &s;
+            for _ in 0..n {
+                match point.force() {
+                    &Nil => break,
+                    &Cons(_, ref s) => point = s
+                }
+            }
+            point.force().clone()
+        })
+    }
+
+    // reverse はモノリシック
+    pub fn reverse(&self) -> Stream<T> {
+        let s = self.clone();
+        Stream::lazy(move || {
+            s.iter().fold(Stream::empty(), |r, x| {
+                Stream::cons(x.clone(), r)
+            }).force().clone()
+        })
+    }
+}
+
+pub struct Iter<'a, T: 'a> {
+    point: &'a Stream<T>
+}
+
+impl <'a, T> Iterator for Iter<'a, T>
+    where T: Clone + 'static
+{
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        match self.point.force() {
+            &Nil => None,
+            &Cons(ref x, ref s) => {
+                self.point = s;
+                Some(x)
+            }
+        }
+    }
+}
+
+impl <T> fmt::Debug for Stream<T>
+    where T: fmt::Debug + Clone + 'static
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[")?;
+        for (i, v) in self.iter().enumerate() {
+            if i != 0 { write!(f, ", ")? }
+            write!(f, "{:?}", v)?;
+        }
+        write!(f, "]")
+    }
+}
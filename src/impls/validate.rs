@@ -0,0 +1,41 @@
+/// 不変条件の検査
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvariantError {
+    // 親の要素が子の要素より後に来る
+    HeapOrder(String),
+    // 左偏ヒープの階数や、二項ヒープの木の階数の並びが正しくない
+    Rank(String),
+    // 重み優先左偏ヒープで、左の子の要素数が右の子より少ない
+    Weight(String),
+    // 階数 r の二項木の要素数が 2^r でない。連結可能両端キューのバッファの要素数が範囲外
+    Size(String),
+    // 保持している最小要素が実際の最小要素と異なる
+    CachedMin(String),
+    // 二分探索木の順序が正しくない
+    Order(String),
+    // 赤い節点の子が赤い。連結可能両端キューの優先パスが Red で終わってはいけない所で終わる
+    Color(String),
+    // 根から葉までの黒い節点の数が揃っていない
+    Unbalanced(String)
+}
+
+impl fmt::Display for InvariantError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &InvariantError::HeapOrder(ref s) => write!(f, "Heap order error! {}", s),
+            &InvariantError::Rank(ref s) => write!(f, "Rank error! {}", s),
+            &InvariantError::Weight(ref s) => write!(f, "Weight error! {}", s),
+            &InvariantError::Size(ref s) => write!(f, "Size error! {}", s),
+            &InvariantError::CachedMin(ref s) => write!(f, "Cached min error! {}", s),
+            &InvariantError::Order(ref s) => write!(f, "Not btree! {}", s),
+            &InvariantError::Color(ref s) => write!(f, "Color error! {}", s),
+            &InvariantError::Unbalanced(ref s) => write!(f, "Unbalanced! {}", s)
+        }
+    }
+}
+
+pub trait Validate {
+    fn check_invariants(&self) -> Result<(), InvariantError>;
+}
@@ -0,0 +1,194 @@
+/// 演習 3.4 重み優先左偏ヒープ
+use std::rc::Rc;
+use std::mem;
+use std::fmt;
+use std::iter::FromIterator;
+
+use super::{Heap, OrderedHeap, SizedHeap, Natural, SortedIter, Validate, InvariantError};
+
+// 左の子の要素数が右の子の要素数以上になるように保つ
+pub struct WLHeapNode<T> {
+  pub size: usize,
+  pub node: T,
+  pub left: WLHeap<T>,
+  pub right: WLHeap<T>
+}
+
+#[derive(Clone)]
+pub enum WLHeap<T> {
+    Empty,
+    Tree(Rc<WLHeapNode<T>>)
+}
+use self::WLHeap::*;
+
+impl <T> WLHeap<T> {
+    pub fn size(&self) -> usize {
+        match *self {
+            Empty => 0,
+            Tree(ref wh) => wh.size
+        }
+    }
+
+    // size は呼び出し側で計算済みのものを渡す
+    fn make(size: usize, node: T, a: WLHeap<T>, b: WLHeap<T>) -> WLHeap<T> {
+        if a.size() >= b.size() {
+            Tree(Rc::new(WLHeapNode {size, node, left: a, right: b}))
+        } else {
+            Tree(Rc::new(WLHeapNode {size, node, left: b, right: a}))
+        }
+    }
+}
+
+// 他から共有されていない節点なら、子を取り外して stack に積む
+fn take_children<T>(h: &mut WLHeap<T>, stack: &mut Vec<WLHeap<T>>) {
+    if let Tree(ref mut wh) = *h {
+        if let Some(node) = Rc::get_mut(wh) {
+            if let Tree(_) = node.left {
+                stack.push(mem::replace(&mut node.left, Empty));
+            }
+            if let Tree(_) = node.right {
+                stack.push(mem::replace(&mut node.right, Empty));
+            }
+        }
+    }
+}
+
+// 降順に insert すると左の枝が n 段になる。LHeap と同じく子を取り外してから落とす
+impl <T> Drop for WLHeap<T> {
+    fn drop(&mut self) {
+        let mut stack = Vec::new();
+        take_children(self, &mut stack);
+        while let Some(mut h) = stack.pop() {
+            take_children(&mut h, &mut stack);
+        }
+    }
+}
+
+impl <T> Heap<T> for WLHeap<T>
+    where T: Ord + Clone
+{
+    fn empty() -> WLHeap<T> {WLHeap::Empty}
+    fn is_empty(&self) -> bool {
+        match *self {
+            Empty => true,
+            _ => false
+        }
+    }
+
+    // 演習 3.4 (c) 子の要素数は merge する前からわかるので、
+    // 左右の入れ替えを先に決めてから下に向かって 1 回で merge する
+    fn merge(&self, other: &WLHeap<T>) -> WLHeap<T> {
+        match (self, other) {
+            (&Empty, h) => h.clone(),
+            (h, &Empty) => h.clone(),
+            (&Tree(ref wh1), &Tree(ref wh2)) => {
+                let size = wh1.size + wh2.size;
+                let (top, rest) = if wh1.node <= wh2.node { (wh1, other) } else { (wh2, self) };
+                let merged_size = top.right.size() + rest.size();
+                if top.left.size() >= merged_size {
+                    Tree(Rc::new(WLHeapNode {
+                        size,
+                        node: top.node.clone(),
+                        left: top.left.clone(),
+                        right: top.right.merge(rest)
+                    }))
+                } else {
+                    Tree(Rc::new(WLHeapNode {
+                        size,
+                        node: top.node.clone(),
+                        left: top.right.merge(rest),
+                        right: top.left.clone()
+                    }))
+                }
+            }
+        }
+    }
+
+    // merge を経由せずに直接挿入する
+    fn insert(&self, node: T) -> WLHeap<T> {
+        match self {
+            &Empty => Tree(Rc::new(WLHeapNode {size: 1, node, left: Empty, right: Empty})),
+            &Tree(ref wh) => {
+                if node <= wh.node {
+                    Tree(Rc::new(WLHeapNode {size: wh.size + 1, node, left: self.clone(), right: Empty}))
+                } else {
+                    WLHeap::make(wh.size + 1, wh.node.clone(), wh.left.clone(), wh.right.insert(node))
+                }
+            }
+        }
+    }
+
+    fn find_min(&self) -> &T {
+        match self {
+            &Empty => panic!("empty heap"),
+            &Tree(ref wh) => &wh.node
+        }
+    }
+
+    fn delete_min(&self) -> WLHeap<T> {
+        match self {
+            &Empty => panic!("empty heap"),
+            &Tree(ref wh) => wh.left.merge(&wh.right)
+        }
+    }
+}
+
+impl <T> OrderedHeap<T> for WLHeap<T>
+    where T: Ord + Clone
+{
+    type Order = Natural;
+}
+
+impl <T> SizedHeap<T> for WLHeap<T>
+    where T: Ord + Clone
+{
+    fn len(&self) -> usize {
+        self.size()
+    }
+}
+
+impl <T> FromIterator<T> for WLHeap<T>
+    where T: Ord + Clone
+{
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> WLHeap<T> {
+        <WLHeap<T> as Heap<T>>::from_iter(iter)
+    }
+}
+
+impl <T> IntoIterator for WLHeap<T>
+    where T: Ord + Clone
+{
+    type Item = T;
+    type IntoIter = SortedIter<WLHeap<T>, T>;
+    fn into_iter(self) -> SortedIter<WLHeap<T>, T> {
+        self.into_sorted_iter()
+    }
+}
+
+impl <T> Validate for WLHeap<T>
+    where T: Ord + fmt::Debug
+{
+    fn check_invariants(&self) -> Result<(), InvariantError> {
+        let mut stack = vec![self];
+        while let Some(h) = stack.pop() {
+            if let &Tree(ref wh) = h {
+                for child in [&wh.left, &wh.right].iter() {
+                    if let &&Tree(ref c) = child {
+                        if c.node < wh.node {
+                            return Err(InvariantError::HeapOrder(format!("child {:?} comes before parent {:?}.", c.node, wh.node)))
+                        }
+                    }
+                }
+                if wh.left.size() < wh.right.size() {
+                    return Err(InvariantError::Weight(format!("On node {:?}, left size is {} but right is {}.", wh.node, wh.left.size(), wh.right.size())))
+                }
+                if wh.size != wh.left.size() + wh.right.size() + 1 {
+                    return Err(InvariantError::Size(format!("Node {:?} records {} elements but has {}.", wh.node, wh.size, wh.left.size() + wh.right.size() + 1)))
+                }
+                stack.push(&wh.left);
+                stack.push(&wh.right);
+            }
+        }
+        Ok(())
+    }
+}